@@ -1,4 +1,5 @@
 use clap::{Parser, ValueHint};
+use shared::{GuardArgs, ReportArgs};
 use std::fs::metadata;
 use std::path::PathBuf;
 
@@ -17,9 +18,44 @@ pub struct Args {
   #[arg(short, long, required = false, value_hint = ValueHint::FilePath, default_value = "concatenated.list")]
   pub output: String,
 
+  /// Write every list to its own file in this directory, with a manifest.json and a lists_of_lists.txt for offline runs,
+  /// instead of the output file
+  #[arg(long, value_hint = ValueHint::DirPath, conflicts_with = "normalize")]
+  pub mirror: Option<PathBuf>,
+
+  /// Write every valid domain once, lowercase and sorted, instead of the raw lists
+  #[arg(long)]
+  pub normalize: bool,
+
+  /// Add the urls of the lists naming the domain as a comment, only with --normalize
+  #[arg(long, requires = "normalize")]
+  pub annotate: bool,
+
   /// How many times to retry if downloading a list fails
   #[arg(short, long, default_value_t = 3)]
   pub max_retries: u32,
+
+  /// Directory keeping the last good copy of every list, used for conditional requests and when a source is down
+  #[arg(short, long, value_hint = ValueHint::DirPath)]
+  pub cache_dir: Option<PathBuf>,
+
+  /// How many lists are downloaded at the same time
+  #[arg(long, default_value_t = 8)]
+  pub concurrency: usize,
+
+  /// How many lists are downloaded at the same time from a single host
+  #[arg(long, default_value_t = 2)]
+  pub per_host: usize,
+
+  /// Seconds allowed for downloading a single list
+  #[arg(long, default_value_t = 60)]
+  pub timeout: u64,
+
+  #[command(flatten)]
+  pub guard: GuardArgs,
+
+  #[command(flatten)]
+  pub report: ReportArgs,
 }
 
 pub fn get_args() -> Args {
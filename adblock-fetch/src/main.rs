@@ -1,7 +1,11 @@
-use log::info;
+use log::{info, warn};
+use shared::{FetchError, FetchOptions, FetchResult, RunState, enforce_guard, record_run};
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 mod cli;
+mod mirror;
+mod normalize;
 
 type BoxError = Box<dyn std::error::Error>;
 
@@ -10,16 +14,54 @@ async fn main() -> Result<(), BoxError> {
   let args = cli::get_args();
 
   shared::setup_logging(args.debug);
-  let downloads = shared::fetch_lists(Some(args.lists_file), args.max_retries).await?;
+  let fetch_options = FetchOptions {
+    max_retries: args.max_retries,
+    cache_dir: args.cache_dir,
+    concurrency: args.concurrency,
+    per_host: args.per_host,
+    timeout: Duration::from_secs(args.timeout),
+    progress: args.report.progress,
+  };
+  let report = shared::fetch_lists(Some(args.lists_file), &fetch_options).await?;
+  report.publish(&args.report)?;
+  // a lists of lists that can't be read is a configuration error, an empty one or a single list failing is not
+  if let Some(e) = report.failures.into_iter().find(FetchError::is_unreadable_lists_file) {
+    return Err(e.into());
+  }
+  let downloads = report.lists;
+  if downloads.is_empty() {
+    return Err("None of the lists could be downloaded, keeping the previous output".into());
+  }
+  let current_run = RunState { sources: downloads.iter().map(|d| (d.url.clone(), d.valid_lines)).collect() };
+  enforce_guard(&args.guard, &current_run)?;
+
+  if let Some(dir) = &args.mirror {
+    let failed = report.downloads.iter().filter(|d| d.error.is_some()).map(|d| d.url.as_str()).collect();
+    let mirrored = mirror::write_mirror(dir, &downloads, &failed)?;
+    info!("Finished! Mirrored {} lists to 「{}」", mirrored.len(), dir.display());
+  } else {
+    write_output(&args.output, downloads, args.normalize, args.annotate).await?;
+  }
+  if let Err(e) = record_run(&args.guard, &current_run) {
+    warn!("Could not save the state of this run: {}", e);
+  }
+  Ok(())
+}
+
+async fn write_output(output: &str, downloads: Vec<FetchResult>, normalize: bool, annotate: bool) -> Result<(), BoxError> {
   //  Prepare the output file
-  let mut file = File::create(&args.output).await?;
-  for fetch_result in downloads {
-    if let Ok(content) = fetch_result.text {
-      let sep_line = "#".repeat(80);
-      file.write_all(format!("{}\n### {}\n{}\n", sep_line, fetch_result.url, sep_line).as_bytes()).await?;
-      file.write_all(content.as_bytes()).await?;
+  let mut file = File::create(output).await?;
+  if normalize {
+    file.write_all(normalize::normalized_list(&downloads, annotate).as_bytes()).await?;
+  } else {
+    for fetch_result in downloads {
+      if let Ok(content) = fetch_result.text {
+        let sep_line = "#".repeat(80);
+        file.write_all(format!("{}\n### {}\n{}\n", sep_line, fetch_result.url, sep_line).as_bytes()).await?;
+        file.write_all(content.as_bytes()).await?;
+      }
     }
   }
-  info!("Finished! Saved all list contents to 「{}」", args.output);
+  info!("Finished! Saved all list contents to 「{}」", output);
   Ok(())
 }
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use shared::{FetchResult, cache_key, hex_sha256};
+
+/// Describes every list in the mirror
+const MANIFEST: &str = "manifest.json";
+/// Lists of lists pointing at the mirrored files, for dns-block runs without network access
+const LISTS_OF_LISTS: &str = "lists_of_lists.txt";
+/// The mirrored lists are kept apart, so the mirror directory itself is never mistaken for a list
+const LISTS_DIR: &str = "lists";
+
+/// Entry of the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MirroredList {
+  pub url: String,
+  /// Path relative to the mirror directory
+  pub file: String,
+  pub sha256: String,
+  /// RFC 3339 time of the download the file holds
+  pub fetched_at: String,
+  /// The download failed and the file holds the cached copy of an earlier one
+  #[serde(default)]
+  pub from_cache: bool,
+  pub lines: usize,
+  pub valid_lines: usize,
+  pub format: String,
+  pub action: String,
+  pub tag: Option<String>,
+}
+
+/// Stable file name for the list at url, named like its copy in the download cache,
+/// e.g. v.firebog.net_hosts_AdguardDNS.txt-1a2b3c4d5e6f7a8b.list
+pub fn file_name(url: &str) -> String {
+  format!("{}.list", cache_key(url))
+}
+
+/// Writes every list to its own file with a manifest and a lists of lists next to it
+/// the lists that failed this time keep their previous copy, files of sources that are gone are removed
+pub fn write_mirror(dir: &Path, lists: &[FetchResult], failed: &HashSet<&str>) -> io::Result<Vec<MirroredList>> {
+  let lists_dir = dir.join(LISTS_DIR);
+  fs::create_dir_all(&lists_dir)?;
+  let fetched_at = Utc::now().to_rfc3339();
+  let previous_entries = read_manifest(dir);
+
+  let mut entries = Vec::with_capacity(lists.len());
+  for list in lists {
+    let Ok(content) = &list.text else {
+      continue;
+    };
+    let file = format!("{}/{}", LISTS_DIR, file_name(&list.url));
+    write_atomically(&dir.join(&file), content.as_bytes())?;
+    let sha256 = hex_sha256(content.as_bytes());
+    // a 304 confirms the cached copy is current, any other cache hit stands in for a failed download
+    let from_cache = list.cache_hit && list.status != Some(304);
+    let fetched_at = previous_entries
+      .iter()
+      .find(|previous| from_cache && previous.url == list.url && previous.sha256 == sha256)
+      .map_or_else(|| fetched_at.clone(), |previous| previous.fetched_at.clone());
+    entries.push(MirroredList {
+      url: list.url.clone(),
+      file,
+      sha256,
+      fetched_at,
+      from_cache,
+      lines: content.lines().count(),
+      valid_lines: list.valid_lines,
+      format: list.format.to_string(),
+      action: list.options.action.to_string(),
+      tag: list.options.tag.clone(),
+    });
+  }
+
+  for previous in previous_entries {
+    if failed.contains(previous.url.as_str()) && dir.join(&previous.file).is_file() {
+      warn!("Keeping the mirrored copy of 「{}」 from {}", previous.url, previous.fetched_at);
+      entries.push(previous);
+    }
+  }
+  entries.sort_by(|a, b| a.url.cmp(&b.url));
+
+  let prefix = format!("{}/", LISTS_DIR);
+  let kept: HashSet<&str> = entries.iter().filter_map(|entry| entry.file.strip_prefix(prefix.as_str())).collect();
+  for file in fs::read_dir(&lists_dir)?.filter_map(|entry| entry.ok()) {
+    if !kept.contains(file.file_name().to_string_lossy().as_ref()) {
+      info!("Removing 「{}」, its source is gone", file.path().display());
+      fs::remove_file(file.path())?;
+    }
+  }
+
+  write_atomically(&dir.join(MANIFEST), serde_json::to_string_pretty(&entries)?.as_bytes())?;
+  write_atomically(&dir.join(LISTS_OF_LISTS), lists_of_lists(&entries).as_bytes())?;
+  Ok(entries)
+}
+
+/// The previous manifest, empty if there is none or it can't be read
+fn read_manifest(dir: &Path) -> Vec<MirroredList> {
+  let Ok(content) = fs::read_to_string(dir.join(MANIFEST)) else {
+    return Vec::new();
+  };
+  serde_json::from_str(&content).unwrap_or_else(|e| {
+    warn!("Ignoring the broken manifest in 「{}」: {}", dir.display(), e);
+    Vec::new()
+  })
+}
+
+/// Pins every file to its hash, so a file changed after mirroring is noticed
+fn lists_of_lists(entries: &[MirroredList]) -> String {
+  let mut out = String::from("# mirrored by adblock-fetch, see manifest.json for the original urls\n");
+  for entry in entries {
+    out.push_str(&format!("{} sha256={} format={} action={}", entry.file, entry.sha256, entry.format, entry.action));
+    if let Some(tag) = &entry.tag {
+      out.push_str(&format!(" tag={}", tag));
+    }
+    out.push('\n');
+  }
+  out
+}
+
+/// Readers of the mirror never see a half written file
+fn write_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+  let tmp = path.with_extension("tmp");
+  fs::write(&tmp, content)?;
+  fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use shared::{FetchResult, ListFormat, SourceOptions};
+
+  use super::{file_name, write_mirror};
+
+  fn list(url: &str, content: &str) -> FetchResult {
+    FetchResult {
+      url: url.to_string(),
+      text: Ok(content.to_string()),
+      valid_lines: content.lines().count(),
+      format: ListFormat::Plain,
+      options: SourceOptions { tag: Some("ads".to_string()), ..SourceOptions::default() },
+      status: Some(200),
+      bytes: content.len(),
+      cache_hit: false,
+    }
+  }
+
+  #[test]
+  fn file_name_test() {
+    let name = file_name("https://v.firebog.net/hosts/AdguardDNS.txt?x=1&y=2");
+    assert!(name.starts_with("v.firebog.net_hosts_AdguardDNS.txt_x_1_y_2-"));
+    assert!(name.ends_with(".list"));
+    assert_eq!(name, file_name("https://v.firebog.net/hosts/AdguardDNS.txt?x=1&y=2"));
+    assert_ne!(file_name("https://example.com/a?b"), file_name("https://example.com/a&b"));
+  }
+
+  #[test]
+  fn mirror_test() {
+    let dir = std::env::temp_dir().join(format!("adblock-fetch-mirror-test-{}", std::process::id()));
+    let a = "https://example.com/a";
+    let b = "https://example.com/b";
+    let first = write_mirror(&dir, &[list(a, "ads.example.com\n"), list(b, "tracker.example.com\n")], &HashSet::new()).unwrap();
+    assert_eq!(2, first.len());
+    assert_eq!(Some("ads".to_string()), first[0].tag);
+
+    // b failed to download and keeps its copy, a is gone from the lists of lists
+    let second = write_mirror(&dir, &[], &HashSet::from([b])).unwrap();
+    assert_eq!(vec![first[1].clone()], second);
+    assert!(!dir.join(&first[0].file).exists());
+    assert_eq!("tracker.example.com\n", std::fs::read_to_string(dir.join(&second[0].file)).unwrap());
+
+    let lists_of_lists = std::fs::read_to_string(dir.join("lists_of_lists.txt")).unwrap();
+    assert!(
+      lists_of_lists.contains(&format!("{} sha256={} format=plain action=nxdomain tag=ads", second[0].file, second[0].sha256))
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn cache_fallback_test() {
+    let dir = std::env::temp_dir().join(format!("adblock-fetch-fallback-test-{}", std::process::id()));
+    let a = "https://example.com/a";
+    let first = write_mirror(&dir, &[list(a, "ads.example.com\n")], &HashSet::new()).unwrap();
+    assert!(!first[0].from_cache);
+
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    // the download failed, the cached copy keeps the time it was fetched
+    let fallback = FetchResult { status: None, cache_hit: true, ..list(a, "ads.example.com\n") };
+    let second = write_mirror(&dir, &[fallback], &HashSet::new()).unwrap();
+    assert!(second[0].from_cache);
+    assert_eq!(first[0].fetched_at, second[0].fetched_at);
+
+    // not modified, the copy is current
+    let not_modified = FetchResult { status: Some(304), cache_hit: true, ..list(a, "ads.example.com\n") };
+    let third = write_mirror(&dir, &[not_modified], &HashSet::new()).unwrap();
+    assert!(!third[0].from_cache);
+    assert_ne!(first[0].fetched_at, third[0].fetched_at);
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use shared::{FetchResult, is_valid_domain};
+
+/// Every valid domain of the lists, lowercase and sorted, with the indexes of the lists naming it
+pub fn collect_domains(lists: &[FetchResult]) -> BTreeMap<String, Vec<usize>> {
+  let mut domains: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+  for (index, list) in lists.iter().enumerate() {
+    let Ok(content) = &list.text else {
+      continue;
+    };
+    for line in content.lines() {
+      list.format.domains(line, |name| {
+        if is_valid_domain(name) {
+          let sources = domains.entry(name.to_ascii_lowercase()).or_default();
+          // a list naming the same domain twice still counts once
+          if sources.last() != Some(&index) {
+            sources.push(index);
+          }
+        }
+      });
+    }
+  }
+  domains
+}
+
+/// A plain list with one domain per line, annotated with the urls of the lists it came from if asked for
+/// the annotation is a comment, so the result can be fed to dns-block as it is
+pub fn normalized_list(lists: &[FetchResult], annotate: bool) -> String {
+  let domains = collect_domains(lists);
+  let mut out = String::with_capacity(domains.len() * 32);
+  let _ = writeln!(out, "# {} unique domains from {} lists", domains.len(), lists.len());
+  for (domain, sources) in domains {
+    out.push_str(&domain);
+    if annotate {
+      let urls: Vec<&str> = sources.iter().map(|&index| lists[index].url.as_str()).collect();
+      let _ = write!(out, " # {}", urls.join(" "));
+    }
+    out.push('\n');
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use shared::{FetchResult, ListFormat, SourceOptions};
+
+  use super::normalized_list;
+
+  fn list(url: &str, format: ListFormat, content: &str) -> FetchResult {
+    FetchResult {
+      url: url.to_string(),
+      text: Ok(content.to_string()),
+      valid_lines: 0,
+      format,
+      options: SourceOptions::default(),
+      status: None,
+      bytes: content.len(),
+      cache_hit: false,
+    }
+  }
+
+  #[test]
+  fn normalized_list_test() {
+    let lists = vec![
+      list(
+        "https://a.example/hosts",
+        ListFormat::Hosts,
+        "# hosts\n127.0.0.1 localhost\n0.0.0.0 Ads.Example.com\n0.0.0.0 ads.example.com\n",
+      ),
+      list("https://b.example/abp", ListFormat::Adblock, "! title\n||ads.example.com^\n||tracker.example.net^\n||x.com/path\n"),
+    ];
+    assert_eq!("# 2 unique domains from 2 lists\nads.example.com\ntracker.example.net\n", normalized_list(&lists, false));
+    assert_eq!(
+      "# 2 unique domains from 2 lists\n\
+       ads.example.com # https://a.example/hosts https://b.example/abp\n\
+       tracker.example.net # https://b.example/abp\n",
+      normalized_list(&lists, true)
+    );
+  }
+}
@@ -5,10 +5,20 @@ use shared::{ManExample, generate_man_page};
 fn main() -> std::io::Result<()> {
   // 1. Build the command from the struct
   let cmd = Args::command();
-  let examples = vec![ManExample {
-    title: "Create the rpz.db file from multiple block lists and an allow list:",
-    example: "dns-block -dd -lists-file list_of_lists.txt own_list_of_lists.txt -block-file hosts_blocked.txt -allow-file domains.whitelisted pack --bind rpz.db",
-  }];
+  let examples = vec![
+    ManExample {
+      title: "Create the rpz.db file from multiple block lists and an allow list:",
+      example: "dns-block -dd -lists-file list_of_lists.txt own_list_of_lists.txt -block-file hosts_blocked.txt -allow-file domains.whitelisted pack --bind rpz.db",
+    },
+    ManExample {
+      title: "Show a summary of the query log refreshed every 5 seconds:",
+      example: "tail -F /var/log/named/query.log | dns-block report --refresh 5",
+    },
+    ManExample {
+      title: "Name the clients of the query log while marking the blocked queries, in one pass:",
+      example: "tail -F /var/log/named/query.log | dns-block pipe -m /etc/bind9/zones -s 10.0.0.0/22 --template '{name}({ip})'",
+    },
+  ];
   generate_man_page(cmd, examples)?;
 
   Ok(())
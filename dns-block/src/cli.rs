@@ -1,7 +1,9 @@
-use std::{fs::metadata, path::PathBuf};
+use std::{fs::metadata, net::SocketAddr, path::PathBuf};
 
 use clap::{Parser, Subcommand, ValueHint};
+use dns_filter_lib::cli::{NameArgs, RewriteArgs};
 use log::trace;
+use shared::{GuardArgs, ReportArgs};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about)]
@@ -31,6 +33,28 @@ pub struct Args {
   /// How many times to retry if downloading a list fails
   #[arg(short, long, default_value_t = 10)]
   pub max_retries: u32,
+
+  /// Directory keeping the last good copy of every list, used for conditional requests and when a source is down
+  #[arg(short, long, value_hint = ValueHint::DirPath)]
+  pub cache_dir: Option<PathBuf>,
+
+  /// How many lists are downloaded at the same time
+  #[arg(long, default_value_t = 8)]
+  pub concurrency: usize,
+
+  /// How many lists are downloaded at the same time from a single host
+  #[arg(long, default_value_t = 2)]
+  pub per_host: usize,
+
+  /// Seconds allowed for downloading a single list
+  #[arg(long, default_value_t = 60)]
+  pub timeout: u64,
+
+  #[command(flatten)]
+  pub guard: GuardArgs,
+
+  #[command(flatten)]
+  pub report: ReportArgs,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -49,6 +73,53 @@ pub enum Commands {
     /// Filter for just these client IPs (comma separated list)
     #[arg(short, long)]
     filter: Option<String>,
+    /// Names the clients and servers of every line as dns-filter does, when a source of names is given
+    #[command(flatten)]
+    names: NameArgs,
+    #[command(flatten)]
+    rewrite: RewriteArgs,
+  },
+  /// Aggregate the Bind9 query log into top domains and clients
+  Report {
+    /// Filter for just these client IPs (comma separated list)
+    #[arg(short, long)]
+    filter: Option<String>,
+    /// How many entries to show in each table
+    #[arg(short = 'n', long, default_value_t = 10)]
+    top: usize,
+    /// Redraw the summary every that many seconds instead of only at the end
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+    refresh: Option<u64>,
+    /// Query log to read, stdin if missing
+    #[arg(name = "input_file", value_hint = ValueHint::FilePath, value_parser = validate_readable_file)]
+    input_file: Option<PathBuf>,
+  },
+  /// Rank frequently queried but unblocked domains that look like trackers
+  Discover {
+    /// Filter for just these client IPs (comma separated list)
+    #[arg(short, long)]
+    filter: Option<String>,
+    /// How many candidates to output
+    #[arg(short = 'n', long, default_value_t = 50)]
+    top: usize,
+    /// Ignore domains queried fewer times than this
+    #[arg(short, long, default_value_t = 5)]
+    min_queries: usize,
+    /// Query log to read, stdin if missing
+    #[arg(name = "input_file", value_hint = ValueHint::FilePath, value_parser = validate_readable_file)]
+    input_file: Option<PathBuf>,
+  },
+  /// Consume the Bind9 query log and expose Prometheus metrics over HTTP
+  Serve {
+    /// Address to listen on, metrics are served under /metrics
+    #[arg(short, long, default_value = "127.0.0.1:9798")]
+    listen: SocketAddr,
+    /// Filter for just these client IPs (comma separated list)
+    #[arg(short, long)]
+    filter: Option<String>,
+    /// Query log to read, stdin if missing
+    #[arg(name = "input_file", value_hint = ValueHint::FilePath, value_parser = validate_readable_file)]
+    input_file: Option<PathBuf>,
   },
 }
 
@@ -0,0 +1,207 @@
+use crate::filter::{is_domain_blocked, open_input, parse_ip_filter, parse_query_line};
+use crate::sub_domains::{Blacklist, sub_domain_iterator};
+use addr::parse_dns_name;
+use fnv::FnvHashMap as HashMap;
+use fnv::FnvHashSet as HashSet;
+use log::*;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// First labels that usually belong to ad and tracking endpoints
+const TRACKER_LABELS: [&str; 22] = [
+  "ad",
+  "ads",
+  "affiliate",
+  "adserver",
+  "adservice",
+  "analytics",
+  "beacon",
+  "beacons",
+  "collect",
+  "collector",
+  "doubleclick",
+  "events",
+  "log",
+  "logs",
+  "metric",
+  "metrics",
+  "pixel",
+  "stats",
+  "telemetry",
+  "track",
+  "tracker",
+  "tracking",
+];
+
+/// An unblocked domain worth looking at
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+  pub domain: String,
+  pub queries: usize,
+  // blocked entries sharing the registrable domain of the candidate
+  pub blocked_siblings: usize,
+  pub tracker_label: bool,
+  pub score: f64,
+}
+
+/// Counts how many blocked entries live under every parent domain
+fn count_blocked_parents<'a>(blacklist_com: &Blacklist<'a>, blacklist_net: &Blacklist<'a>) -> HashMap<&'a str, usize> {
+  let mut counts = HashMap::default();
+  for domain in blacklist_com.keys().chain(blacklist_net.keys()) {
+    for parent in sub_domain_iterator(domain, 1) {
+      *counts.entry(parent).or_insert(0) += 1;
+    }
+  }
+  counts
+}
+
+fn has_tracker_label(domain: &str) -> bool {
+  domain.split('.').next().is_some_and(|label| {
+    let label = label.trim_end_matches(|c: char| c.is_ascii_digit() || c == '-');
+    TRACKER_LABELS.contains(&label)
+  })
+}
+
+/// Scores the unblocked domains, more queries, more blocked siblings and a tracker looking name rank higher
+pub fn rank_candidates(
+  queries: &HashMap<String, usize>,
+  blocked_parents: &HashMap<&str, usize>,
+  whitelist: &HashSet<&str>,
+  min_queries: usize,
+) -> Vec<Candidate> {
+  let mut candidates: Vec<Candidate> = queries
+    .iter()
+    .filter(|(domain, count)| **count >= min_queries && !whitelist.contains(domain.as_str()))
+    .filter_map(|(domain, count)| {
+      let root = parse_dns_name(domain).ok()?.root()?;
+      let blocked_siblings = blocked_parents.get(root).copied().unwrap_or(0);
+      let tracker_label = has_tracker_label(domain);
+      if blocked_siblings == 0 && !tracker_label {
+        return None;
+      }
+      let mut score = *count as f64 * (1.0 + (blocked_siblings as f64).ln_1p());
+      if tracker_label {
+        score *= 2.0;
+      }
+      Some(Candidate { domain: domain.clone(), queries: *count, blocked_siblings, tracker_label, score })
+    })
+    .collect();
+  candidates.sort_unstable_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.domain.cmp(&b.domain)));
+  candidates
+}
+
+/// Writes the candidates in the block file format, the explanation goes in a comment
+pub fn write_candidates(out: &mut impl Write, candidates: &[Candidate]) -> io::Result<()> {
+  writeln!(out, "# dns-block discover: frequently queried domains that are not blocked")?;
+  for c in candidates {
+    write!(out, "{} # score {:.1}, {} queries", c.domain, c.score, c.queries)?;
+    if c.blocked_siblings > 0 {
+      write!(out, ", {} blocked siblings", c.blocked_siblings)?;
+    }
+    if c.tracker_label {
+      write!(out, ", tracker label")?;
+    }
+    writeln!(out)?;
+  }
+  Ok(())
+}
+
+/// Consumes the query log and prints a ranked list of blocking candidates
+pub fn discover(
+  blacklist_com: &Blacklist,
+  blacklist_net: &Blacklist,
+  whitelist: &HashSet<&str>,
+  filter_parameter: Option<&str>,
+  input_file: Option<&Path>,
+  top: usize,
+  min_queries: usize,
+) -> io::Result<()> {
+  debug!("Discover for client ips: {:#?}", filter_parameter);
+  let ip_filter = parse_ip_filter(filter_parameter);
+  let mut input = open_input(input_file)?;
+  let mut line = String::new();
+  let mut domain = String::new();
+  let mut queries: HashMap<String, usize> = HashMap::default();
+
+  loop {
+    let n = input.read_line(&mut line)?;
+    if n == 0 {
+      break;
+    }
+    if let Some((client, queried)) = parse_query_line(&line)
+      && (ip_filter.is_empty() || ip_filter.contains(&client))
+    {
+      // resolvers may randomize the case of the query
+      domain.truncate(0);
+      domain.push_str(queried);
+      domain.make_ascii_lowercase();
+      if !domain.ends_with(".arpa") && !is_domain_blocked(&domain, blacklist_com, blacklist_net) {
+        match queries.get_mut(&domain) {
+          Some(count) => *count += 1,
+          None => {
+            queries.insert(domain.clone(), 1);
+          }
+        }
+      }
+    }
+    line.truncate(0);
+  }
+  debug!("{} distinct unblocked domains in the query log", queries.len());
+
+  let blocked_parents = count_blocked_parents(blacklist_com, blacklist_net);
+  let mut candidates = rank_candidates(&queries, &blocked_parents, whitelist, min_queries);
+  candidates.truncate(top);
+
+  let stdout = io::stdout();
+  let mut handle = stdout.lock();
+  write_candidates(&mut handle, &candidates)?;
+  handle.flush()
+}
+
+#[cfg(test)]
+mod tests_discover {
+  use super::*;
+  use crate::sub_domains::BlockEntry;
+
+  #[test]
+  fn tracker_label_test() {
+    assert!(has_tracker_label("metrics.example.com"));
+    assert!(has_tracker_label("ads2.example.com"));
+    assert!(has_tracker_label("telemetry-1.example.com"));
+    assert!(!has_tracker_label("www.example.com"));
+    assert!(!has_tracker_label("adsense-free.example.com"));
+  }
+
+  #[test]
+  fn ranking_test() {
+    let entry = BlockEntry { distinct: true, source: 0 };
+    let mut blacklist_com = Blacklist::default();
+    blacklist_com.insert("a.tracker.com", entry);
+    blacklist_com.insert("b.tracker.com", entry);
+    let blacklist_net = Blacklist::default();
+    let blocked_parents = count_blocked_parents(&blacklist_com, &blacklist_net);
+    assert_eq!(Some(&2), blocked_parents.get("tracker.com"));
+
+    let mut queries = HashMap::default();
+    queries.insert("c.tracker.com".to_string(), 10);
+    queries.insert("telemetry.vendor.net".to_string(), 10);
+    queries.insert("www.innocent.org".to_string(), 100);
+    queries.insert("metrics.rare.org".to_string(), 1);
+    queries.insert("ads.allowed.net".to_string(), 50);
+    let mut whitelist = HashSet::default();
+    whitelist.insert("ads.allowed.net");
+
+    let candidates = rank_candidates(&queries, &blocked_parents, &whitelist, 2);
+    let domains: Vec<&str> = candidates.iter().map(|c| c.domain.as_str()).collect();
+    assert_eq!(vec!["c.tracker.com", "telemetry.vendor.net"], domains);
+    assert_eq!(2, candidates[0].blocked_siblings);
+    assert!(candidates[1].tracker_label);
+
+    let mut out = Vec::new();
+    write_candidates(&mut out, &candidates[..1]).unwrap();
+    assert_eq!(
+      "# dns-block discover: frequently queried domains that are not blocked\nc.tracker.com # score 21.0, 10 queries, 2 blocked siblings\n",
+      String::from_utf8(out).unwrap()
+    );
+  }
+}
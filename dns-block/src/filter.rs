@@ -1,24 +1,29 @@
-use crate::sub_domains::sub_domain_iterator;
-use fnv::FnvHashMap as HashMap;
+use crate::sub_domains::{Blacklist, BlockEntry, sub_domain_iterator};
+use dns_filter_lib::cli::{NameArgs, RewriteArgs};
+use dns_filter_lib::enrich::{Enricher, Preset};
+use dns_filter_lib::sources::LiveNames;
 use fnv::FnvHashSet as HashSet;
 use log::*;
-use std::io::{self, Write};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
 
-fn is_domain_blocked_by_index(domain: &str, index: &HashMap<&str, bool>) -> bool {
-  for seg in sub_domain_iterator(domain, 1) {
-    if index.contains_key(seg) {
-      return true;
-    }
-  }
-  false
+fn find_in_index<'a>(domain: &str, index: &'a Blacklist) -> Option<&'a BlockEntry> {
+  sub_domain_iterator(domain, 1).find_map(|seg| index.get(seg))
 }
 
-fn is_domain_blocked(domain: &str, blacklist_com: &HashMap<&str, bool>, blacklist_net: &HashMap<&str, bool>) -> bool {
-  if domain.ends_with("com") {
-    is_domain_blocked_by_index(domain, blacklist_com)
-  } else {
-    is_domain_blocked_by_index(domain, blacklist_net)
-  }
+/// Returns the index entry responsible for blocking the domain, if any
+pub(crate) fn find_blocking_entry<'a>(
+  domain: &str,
+  blacklist_com: &'a Blacklist,
+  blacklist_net: &'a Blacklist,
+) -> Option<&'a BlockEntry> {
+  if domain.ends_with("com") { find_in_index(domain, blacklist_com) } else { find_in_index(domain, blacklist_net) }
+}
+
+pub(crate) fn is_domain_blocked(domain: &str, blacklist_com: &Blacklist, blacklist_net: &Blacklist) -> bool {
+  find_blocking_entry(domain, blacklist_com, blacklist_net).is_some()
 }
 
 fn extract<'a>(line: &'a str, pref: &str, suf: &str) -> Option<&'a str> {
@@ -32,10 +37,77 @@ fn extract<'a>(line: &'a str, pref: &str, suf: &str) -> Option<&'a str> {
   None
 }
 
-pub fn filter(
-  blacklist_com: &HashMap<&str, bool>,
-  blacklist_net: &HashMap<&str, bool>,
+/// Extracts the client ip and the queried domain from a Bind9 query log line
+pub(crate) fn parse_query_line(line: &str) -> Option<(&str, &str)> {
+  let domain = extract(line, "query: ", " ")?;
+  let client = extract(line, "client ", "#")?;
+  Some((client, domain))
+}
+
+/// Turns the comma separated list of client ips into a set, empty means no filtering
+pub(crate) fn parse_ip_filter(filter_parameter: Option<&str>) -> HashSet<&str> {
+  match filter_parameter {
+    Some(filter) => filter.split(',').collect::<HashSet<&str>>(),
+    None => HashSet::with_capacity_and_hasher(0, Default::default()),
+  }
+}
+
+/// Opens the query log, stdin if no file is given
+pub(crate) fn open_input(path: Option<&Path>) -> io::Result<Box<dyn BufRead + Send>> {
+  match path {
+    Some(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+    None => Ok(Box::new(BufReader::new(io::stdin()))),
+  }
+}
+
+/// Names the clients and servers of the piped lines, the names follow the files as dns-filter does
+pub(crate) struct Naming {
+  names: LiveNames,
+  enricher: Enricher,
+}
+
+impl Naming {
+  pub(crate) fn start(args: &NameArgs, rewrite: RewriteArgs) -> io::Result<Naming> {
+    let rewrite = rewrite.rewrite(args);
+    Ok(Naming { names: LiveNames::start(args)?, enricher: Enricher { preset: Some(Preset::BindQuery), rewrite, json: false } })
+  }
+
+  fn line<'a>(&mut self, line: &'a str) -> Cow<'a, str> {
+    self.enricher.enrich(self.names.current(), line)
+  }
+
+  fn client(&mut self, client: &str) -> String {
+    self.enricher.rewrite.apply(&self.names.current().lookup(client))
+  }
+}
+
+/// The line written for a query, the query log line as is when the domain is allowed, named and marked allowed when
+/// there are names, the client and domain marked blocked when it is blocked, nothing for the clients filtered out
+fn output_line<'a>(
+  line: &'a str,
+  blacklist_com: &Blacklist,
+  blacklist_net: &Blacklist,
+  ip_filter: &HashSet<&str>,
+  naming: Option<&mut Naming>,
+) -> Option<Cow<'a, str>> {
+  let (client, domain) = parse_query_line(line)?;
+  if !ip_filter.is_empty() && !ip_filter.contains(&client) {
+    return None;
+  }
+  let blocked = is_domain_blocked(domain, blacklist_com, blacklist_net);
+  Some(match (naming, blocked) {
+    (None, false) => Cow::Borrowed(line),
+    (None, true) => Cow::Owned(format!("{} {} blocked\n", client, domain)),
+    (Some(naming), false) => Cow::Owned(format!("{} allowed\n", naming.line(line).trim_end())),
+    (Some(naming), true) => Cow::Owned(format!("{} {} blocked\n", naming.client(client), domain)),
+  })
+}
+
+pub(crate) fn filter(
+  blacklist_com: &Blacklist,
+  blacklist_net: &Blacklist,
   filter_parameter: Option<&str>,
+  mut naming: Option<Naming>,
 ) -> io::Result<()> {
   debug!("Filter for client ips: {:#?}", filter_parameter);
   let mut input = String::new();
@@ -43,26 +115,15 @@ pub fn filter(
   let stdout = io::stdout();
   let mut handle = stdout.lock();
 
-  let ip_filter: HashSet<&str> = match filter_parameter {
-    Some(filter) => filter.split(',').collect::<HashSet<&str>>(),
-    None => HashSet::with_capacity_and_hasher(0, Default::default()),
-  };
+  let ip_filter = parse_ip_filter(filter_parameter);
 
   loop {
     let n = io::stdin().read_line(&mut input)?;
     if n == 0 {
       return Ok(());
     }
-    let domain_opt = extract(&input, "query: ", " ");
-    let client_opt = extract(&input, "client ", "#");
-    if let (Some(domain), Some(client)) = (domain_opt, client_opt)
-      && (ip_filter.is_empty() || ip_filter.contains(&client))
-    {
-      if !is_domain_blocked(domain, blacklist_com, blacklist_net) {
-        handle.write_all(input.as_bytes())?;
-      } else {
-        handle.write_fmt(format_args!("{} {} {}\n", &client, &domain, "blocked"))?;
-      }
+    if let Some(output) = output_line(&input, blacklist_com, blacklist_net, &ip_filter, naming.as_mut()) {
+      handle.write_all(output.as_bytes())?;
     }
 
     input.truncate(0);
@@ -75,7 +136,48 @@ mod tests_filter {
   fn extraction_test() {
     let line =
       "20-Jan-2021 10:10:10.536 client 10.0.0.30#7216 (mydomain.com): view internal: query: mydomain.com IN A + (10.0.0.12)";
-    assert_eq!("10.0.0.30", super::extract(&line, "client ", "#").unwrap());
-    assert_eq!("mydomain.com", super::extract(&line, "query: ", " ").unwrap());
+    assert_eq!("10.0.0.30", super::extract(line, "client ", "#").unwrap());
+    assert_eq!("mydomain.com", super::extract(line, "query: ", " ").unwrap());
+  }
+
+  #[test]
+  fn naming_test() {
+    use clap::Parser;
+
+    let dir = std::env::temp_dir().join(format!("dns-block-naming-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("db.0.0.10"), "$ORIGIN 0.0.10.in-addr.arpa.\n30 PTR phone.lan.\n12 PTR router.lan.\n").unwrap();
+    let dir = dir.display().to_string();
+    let args = crate::cli::Args::parse_from(["dns-block", "pipe", "-m", &dir, "--template", "{name}({ip})", "--no-watch"]);
+    let crate::cli::Commands::Pipe { names, rewrite, .. } = args.command else {
+      panic!("not the pipe command");
+    };
+    let mut naming = super::Naming::start(&names, rewrite).unwrap();
+
+    let mut index = super::Blacklist::default();
+    index.insert("ads.example.com", super::BlockEntry { distinct: true, source: 0 });
+    let no_filter = super::HashSet::default();
+    let line = "20-Jan-2021 10:10:10.536 client 10.0.0.30#7216 (mydomain.com): query: mydomain.com IN A + (10.0.0.12)\n";
+    assert_eq!(
+      "20-Jan-2021 10:10:10.536 client phone.lan(10.0.0.30)#7216 (mydomain.com): query: mydomain.com IN A + (router.lan(10.0.0.12)) allowed\n",
+      super::output_line(line, &index, &index, &no_filter, Some(&mut naming)).unwrap()
+    );
+    assert_eq!(line, super::output_line(line, &index, &index, &no_filter, None).unwrap());
+    let line =
+      "20-Jan-2021 10:10:10.536 client 10.0.0.30#7216 (www.ads.example.com): query: www.ads.example.com IN A + (10.0.0.12)\n";
+    assert_eq!(
+      "phone.lan(10.0.0.30) www.ads.example.com blocked\n",
+      super::output_line(line, &index, &index, &no_filter, Some(&mut naming)).unwrap()
+    );
+    assert_eq!("10.0.0.30 www.ads.example.com blocked\n", super::output_line(line, &index, &index, &no_filter, None).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn blocked_test() {
+    let mut index = super::Blacklist::default();
+    index.insert("ads.example.com", super::BlockEntry { distinct: true, source: 3 });
+    assert_eq!(3, super::find_in_index("many.ads.example.com", &index).unwrap().source);
+    assert!(super::find_in_index("example.com", &index).is_none());
   }
 }
@@ -7,17 +7,22 @@ use std::io::{BufWriter, Write};
 
 use std::sync::mpsc;
 use std::thread;
+use std::time::SystemTime;
 
 mod cli;
 mod dns_resolver;
 mod sub_domains;
-use sub_domains::{Domain, count_char_occurences, sub_domain_iterator};
+use sub_domains::{Blacklist, BlockEntry, Domain, count_char_occurences, sub_domain_iterator};
+mod discover;
 mod filter;
+mod metrics;
+use metrics::Metrics;
+mod report;
 mod statistics;
-use statistics::Statistics;
+use statistics::{Statistics, UNTAGGED, blocked_by_tag, format_tags};
 mod file_config;
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use rayon::join;
 
@@ -25,11 +30,13 @@ use indoc::indoc;
 use log::*;
 
 use mimalloc::MiMalloc;
+use shared::{FetchError, FetchOptions, ListFormat, RunState, SourceOptions, enforce_guard, record_run};
 
 use crate::cli::{Commands, get_args};
 use crate::file_config::get_allow_file;
 use crate::file_config::get_block_files;
 use crate::file_config::get_lists_files;
+use crate::filter::Naming;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -51,7 +58,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
   let start = Instant::now();
 
   debug!("resolve the remote lists");
-  let remote_lists = shared::fetch_lists(lists_files, args.max_retries).await?;
+  let fetch_options = FetchOptions {
+    max_retries: args.max_retries,
+    cache_dir: args.cache_dir,
+    concurrency: args.concurrency,
+    per_host: args.per_host,
+    timeout: Duration::from_secs(args.timeout),
+    progress: args.report.progress,
+  };
+  let report = shared::fetch_lists(lists_files, &fetch_options).await?;
+  report.publish(&args.report)?;
+  // a lists of lists that can't be read is a configuration error, an empty one and the lists that failed
+  // are logged already and the guard catches a run where too many of them did
+  if let Some(e) = report.failures.into_iter().find(FetchError::is_unreadable_lists_file) {
+    return Err(e.into());
+  }
+  let remote_lists = report.lists;
 
   let whitelist_string = match allow_file {
     Some(path) => fs::read_to_string(path).unwrap(),
@@ -69,6 +91,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
   let start_sorting = start.elapsed().as_millis();
 
+  // names of the block lists, a domain refers to its list by index
+  let mut sources: Vec<String> = Vec::new();
+  // valid lines of each block list, for the sanity check against the previous run
+  let mut source_lines: Vec<usize> = Vec::new();
+  // options of each block list, local block files use the defaults
+  let mut source_options: Vec<SourceOptions> = Vec::new();
+
   // read the block files from disk
   // also calculate number of lines
   let mut total_line_count = 0;
@@ -76,6 +105,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Some(block_files) => block_files
       .iter()
       .map(|path| {
+        sources.push(path.display().to_string());
+        source_options.push(SourceOptions::default());
         let mut text = read_to_string(path).unwrap();
         // converting to lowercase might generate some duplicates
         text.make_ascii_lowercase();
@@ -95,21 +126,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
   debug!("add the local lists to stuff to block");
   let mut bad_domains = Vec::with_capacity(total_line_count);
-  block_lists.iter().for_each(|text| {
+  block_lists.iter().enumerate().for_each(|(source, text)| {
+    let format = ListFormat::detect(text);
+    debug!("「{}」 is in the {} format", sources[source], format);
+    source_lines.push(format.count_valid_lines(text));
     for line in text.lines() {
-      if let Some(domain) = Domain::new(line) {
-        bad_domains.push(domain);
-      }
+      Domain::parse_line(line, format, source as u32, |domain| bad_domains.push(domain));
     }
   });
 
   debug!("add the remote lists to the stuff to block");
   remote_lists.iter().for_each(|fetch_result| {
     if let Ok(text) = &fetch_result.text {
+      let source = sources.len() as u32;
+      sources.push(fetch_result.url.clone());
+      source_lines.push(fetch_result.valid_lines);
+      source_options.push(fetch_result.options.clone());
       for line in text.lines() {
-        if let Some(domain) = Domain::new(line) {
-          bad_domains.push(domain);
-        }
+        Domain::parse_line(line, fetch_result.format, source, |domain| bad_domains.push(domain));
       }
     }
   });
@@ -141,19 +175,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
   );
   info!("Statistics .com \n{}", &statistics_com);
   info!("Statistics .net \n{}", &statistics_net);
-  info!("Statistics total \n{}", Statistics::aggregate(&statistics_com, &statistics_net));
+  let statistics_total = Statistics::aggregate(&statistics_com, &statistics_net);
+  info!("Statistics total \n{}", &statistics_total);
+  let tags = blocked_by_tag(&blacklist_com, &blacklist_net, &source_options);
+  if tags.keys().any(|tag| *tag != UNTAGGED) {
+    info!("Blocked by tag \n{}", format_tags(&tags));
+  }
 
   match args.command {
-    Commands::Pipe { filter } => {
-      filter::filter(&blacklist_com, &blacklist_net, filter.as_deref()).unwrap();
+    Commands::Pipe { filter, names, rewrite } => {
+      let naming = if names.is_empty() { None } else { Some(Naming::start(&names, rewrite)?) };
+      filter::filter(&blacklist_com, &blacklist_net, filter.as_deref(), naming).unwrap();
+    }
+    Commands::Report { filter, top, refresh, input_file } => {
+      report::report(&blacklist_com, &blacklist_net, filter.as_deref(), input_file.as_deref(), top, refresh)?;
+    }
+    Commands::Discover { filter, top, min_queries, input_file } => {
+      discover::discover(&blacklist_com, &blacklist_net, &whitelist, filter.as_deref(), input_file.as_deref(), top, min_queries)?;
+    }
+    Commands::Serve { listen, filter, input_file } => {
+      let metrics =
+        Metrics::new(sources, blacklist_com.len() + blacklist_net.len(), &statistics_total, SystemTime::now(), start.elapsed());
+      metrics::serve(&blacklist_com, &blacklist_net, metrics, listen, filter.as_deref(), input_file.as_deref())?;
     }
     Commands::Pack { bind, output_file } => {
+      let current_run = RunState { sources: sources.into_iter().zip(source_lines).collect() };
+      enforce_guard(&args.guard, &current_run)?;
+
       let start_writing = start.elapsed().as_millis();
       if bind {
-        write_bind_output(&blacklist_com, &blacklist_net, &output_file);
+        write_bind_output(&blacklist_com, &blacklist_net, &source_options, &output_file);
       } else {
         write_output(&blacklist_com, &blacklist_net, &output_file);
       }
+      if let Err(e) = record_run(&args.guard, &current_run) {
+        warn!("Could not save the state of this run: {}", e);
+      }
 
       if args.timing {
         info!(
@@ -182,35 +239,28 @@ fn process_whitelist_line<'a>(line: &'a str, index: &mut HashSet<&'a str>) {
 
 /// adds a domain to the blocked index if it's not already blocked or whitelisted
 fn process_bad_domain<'a>(
-  domain: &'a str,
-  index: &mut HashMap<&'a str, bool>,
+  bad_domain: &Domain<'a>,
+  index: &mut Blacklist<'a>,
   whitelist: &HashSet<&'a str>,
   statistics: &mut Statistics,
   whitelisted: &mut HashSet<&'a str>,
 ) {
+  let domain = bad_domain.name;
   if domain.is_empty() {
     return;
   }
   // check if a parent domain is already blocked
   for seg in sub_domain_iterator(domain, 1) {
-    // get the parent and mark it as false
-    if let Some(is_distinct) = index.get_mut(seg) {
-      if *is_distinct {
-        // mark as not distinct anymore
-        *is_distinct = false;
-      }
-      statistics.increment_parent();
-
-      return;
-    }
-    if index.contains_key(seg) {
+    // get the parent and mark it as not distinct anymore
+    if let Some(entry) = index.get_mut(seg) {
+      entry.distinct = false;
       statistics.increment_parent();
 
       return;
     }
   }
   if !whitelist.contains(domain) {
-    if index.insert(domain, true).is_none() {
+    if index.insert(domain, BlockEntry { distinct: true, source: bad_domain.source }).is_none() {
       statistics.increment_blocked();
     } else {
       statistics.increment_duplicate();
@@ -225,7 +275,7 @@ fn process_bad_domain<'a>(
   }
 }
 
-fn write_output(index_com: &HashMap<&str, bool>, index_net: &HashMap<&str, bool>, output_file: &str) {
+fn write_output(index_com: &Blacklist, index_net: &Blacklist, output_file: &str) {
   let mut f = BufWriter::with_capacity(8 * 1024, fs::File::create(output_file).unwrap());
   let eol: [u8; 1] = [10];
   for d in index_com.keys() {
@@ -239,7 +289,8 @@ fn write_output(index_com: &HashMap<&str, bool>, index_net: &HashMap<&str, bool>
   f.flush().unwrap();
 }
 
-fn write_bind_output(index_com: &HashMap<&str, bool>, index_net: &HashMap<&str, bool>, output_file: &str) {
+/// Every domain gets the action of the block list that got it into the index
+fn write_bind_output(index_com: &Blacklist, index_net: &Blacklist, source_options: &[SourceOptions], output_file: &str) {
   let preamble = indoc! {"
         $TTL 60
         @   IN    SOA  localhost. root.localhost.  (
@@ -251,23 +302,25 @@ fn write_bind_output(index_com: &HashMap<&str, bool>, index_net: &HashMap<&str,
             IN    NS    localhost.
     "};
   let prefix = "*.";
-  let suffix = " CNAME .";
+  let suffix = " CNAME ";
   let mut f = BufWriter::with_capacity(8 * 1024, fs::File::create(output_file).unwrap());
 
   f.write_all(preamble.as_bytes()).unwrap();
 
   let eol: [u8; 1] = [10];
-  let mut serialize_index = |index: &HashMap<&str, bool>| {
-    for d in index.iter() {
-      let key = *d.0;
+  let mut serialize_index = |index: &Blacklist| {
+    for (key, entry) in index.iter() {
+      let target = source_options.get(entry.source as usize).map_or(".", |options| options.action.rpz_target());
       f.write_all(key.as_bytes()).unwrap();
       f.write_all(suffix.as_bytes()).unwrap();
+      f.write_all(target.as_bytes()).unwrap();
       f.write_all(&eol).unwrap();
 
-      if !*d.1 {
+      if !entry.distinct {
         f.write_all(prefix.as_bytes()).unwrap();
         f.write_all(key.as_bytes()).unwrap();
         f.write_all(suffix.as_bytes()).unwrap();
+        f.write_all(target.as_bytes()).unwrap();
         f.write_all(&eol).unwrap();
       }
     }
@@ -300,13 +353,13 @@ fn process_baddies<'a>(
   bad_domains: &'a [Domain],
   whitelist: &HashSet<&'a str>,
   filter_d: fn(&str) -> bool,
-) -> (HashMap<&'a str, bool>, Statistics) {
-  let mut blacklist: HashMap<&str, bool> = HashMap::with_capacity_and_hasher(bad_domains.len() / 2, Default::default());
+) -> (Blacklist<'a>, Statistics) {
+  let mut blacklist: Blacklist = HashMap::with_capacity_and_hasher(bad_domains.len() / 2, Default::default());
   let mut whitelisted: HashSet<&str> = HashSet::with_capacity_and_hasher(whitelist.len(), Default::default());
   let mut statistics = Statistics::new();
 
   for domain in bad_domains.iter().filter(|d| filter_d(d.name)) {
-    process_bad_domain(domain.name, &mut blacklist, whitelist, &mut statistics, &mut whitelisted);
+    process_bad_domain(domain, &mut blacklist, whitelist, &mut statistics, &mut whitelisted);
   }
   (blacklist, statistics)
 }
@@ -0,0 +1,218 @@
+use crate::filter::{find_blocking_entry, open_input, parse_ip_filter, parse_query_line};
+use crate::statistics::Statistics;
+use crate::sub_domains::Blacklist;
+use log::*;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Default)]
+struct ClientCounters {
+  queries: u64,
+  blocked: u64,
+}
+
+/// Counters exported in the Prometheus text format
+#[derive(Debug)]
+pub struct Metrics {
+  // sorted so the exposition is stable between scrapes
+  clients: BTreeMap<String, ClientCounters>,
+  sources: Vec<String>,
+  blocked_by_source: Vec<u64>,
+  blocklist_size: usize,
+  index_counters: [(&'static str, usize); 5],
+  pack_timestamp: f64,
+  pack_duration: f64,
+}
+
+impl Metrics {
+  pub fn new(
+    sources: Vec<String>,
+    blocklist_size: usize,
+    statistics: &Statistics,
+    packed_at: SystemTime,
+    pack_duration: Duration,
+  ) -> Metrics {
+    Metrics {
+      clients: BTreeMap::new(),
+      blocked_by_source: vec![0; sources.len()],
+      sources,
+      blocklist_size,
+      index_counters: statistics.counters(),
+      pack_timestamp: packed_at.duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default(),
+      pack_duration: pack_duration.as_secs_f64(),
+    }
+  }
+
+  /// Counts a query, source is the block list that blocked it if any
+  pub fn record(&mut self, client: &str, source: Option<u32>) {
+    let counters = match self.clients.get_mut(client) {
+      Some(counters) => counters,
+      None => self.clients.entry(client.to_string()).or_default(),
+    };
+    counters.queries += 1;
+    if let Some(source) = source {
+      counters.blocked += 1;
+      if let Some(count) = self.blocked_by_source.get_mut(source as usize) {
+        *count += 1;
+      }
+    }
+  }
+
+  pub fn write_metrics(&self, out: &mut impl Write) -> io::Result<()> {
+    header(out, "dns_block_queries_total", "counter", "Queries seen in the query log per client")?;
+    for (client, counters) in &self.clients {
+      writeln!(out, "dns_block_queries_total{{client=\"{}\"}} {}", escape(client), counters.queries)?;
+    }
+
+    header(out, "dns_block_blocked_total", "counter", "Blocked queries per client")?;
+    for (client, counters) in &self.clients {
+      writeln!(out, "dns_block_blocked_total{{client=\"{}\"}} {}", escape(client), counters.blocked)?;
+    }
+
+    header(out, "dns_block_blocked_by_source_total", "counter", "Blocked queries per block list")?;
+    for (source, count) in self.sources.iter().zip(&self.blocked_by_source) {
+      writeln!(out, "dns_block_blocked_by_source_total{{source=\"{}\"}} {}", escape(source), count)?;
+    }
+
+    header(out, "dns_block_blocklist_domains", "gauge", "Domains in the block index")?;
+    writeln!(out, "dns_block_blocklist_domains {}", self.blocklist_size)?;
+
+    header(out, "dns_block_list_entries", "gauge", "Entries read from the block lists by outcome")?;
+    for (kind, count) in &self.index_counters {
+      writeln!(out, "dns_block_list_entries{{kind=\"{}\"}} {}", kind, count)?;
+    }
+
+    header(out, "dns_block_last_pack_timestamp_seconds", "gauge", "When the block index was last built")?;
+    writeln!(out, "dns_block_last_pack_timestamp_seconds {}", self.pack_timestamp)?;
+
+    header(out, "dns_block_last_pack_duration_seconds", "gauge", "How long building the block index took")?;
+    writeln!(out, "dns_block_last_pack_duration_seconds {}", self.pack_duration)?;
+    Ok(())
+  }
+}
+
+fn header(out: &mut impl Write, name: &str, kind: &str, help: &str) -> io::Result<()> {
+  writeln!(out, "# HELP {} {}", name, help)?;
+  writeln!(out, "# TYPE {} {}", name, kind)
+}
+
+/// Escapes a label value as required by the Prometheus text format
+fn escape(value: &str) -> String {
+  value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// A client that stalls is dropped after this, the connections are served one at a time
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn handle_connection(stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+  stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+  stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+  let mut reader = BufReader::new(stream);
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line)?;
+  // skip the headers, we don't need any of them
+  let mut header_line = String::new();
+  while reader.read_line(&mut header_line)? > 2 {
+    header_line.truncate(0);
+  }
+
+  let mut stream = reader.into_inner();
+  let mut parts = request_line.split_whitespace();
+  if let (Some("GET"), Some("/metrics")) = (parts.next(), parts.next()) {
+    let mut body = Vec::with_capacity(4 * 1024);
+    metrics.lock().unwrap().write_metrics(&mut body)?;
+    write!(
+      stream,
+      "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+      body.len()
+    )?;
+    stream.write_all(&body)?;
+  } else {
+    stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+  }
+  stream.flush()
+}
+
+fn serve_http(listener: TcpListener, metrics: Arc<Mutex<Metrics>>) {
+  for stream in listener.incoming() {
+    match stream {
+      Ok(stream) => {
+        if let Err(e) = handle_connection(stream, &metrics) {
+          warn!("Error serving metrics: {}", e);
+        }
+      }
+      Err(e) => warn!("Error accepting a connection: {}", e),
+    }
+  }
+}
+
+/// Consumes the query log and serves the metrics over HTTP, the final counters stay available after the log ends
+pub fn serve(
+  blacklist_com: &Blacklist,
+  blacklist_net: &Blacklist,
+  metrics: Metrics,
+  listen: SocketAddr,
+  filter_parameter: Option<&str>,
+  input_file: Option<&Path>,
+) -> io::Result<()> {
+  let listener = TcpListener::bind(listen)?;
+  info!("Serving metrics on http://{}/metrics", listen);
+  let metrics = Arc::new(Mutex::new(metrics));
+  let server_metrics = Arc::clone(&metrics);
+  let server = thread::spawn(move || serve_http(listener, server_metrics));
+
+  let ip_filter = parse_ip_filter(filter_parameter);
+  let mut input = open_input(input_file)?;
+  let mut line = String::new();
+  loop {
+    let n = input.read_line(&mut line)?;
+    if n == 0 {
+      info!("End of the query log, still serving the metrics");
+      server.join().map_err(|_| io::Error::other("the metrics server panicked"))?;
+      return Ok(());
+    }
+    if let Some((client, domain)) = parse_query_line(&line)
+      && (ip_filter.is_empty() || ip_filter.contains(&client))
+    {
+      let source = find_blocking_entry(domain, blacklist_com, blacklist_net).map(|entry| entry.source);
+      metrics.lock().unwrap().record(client, source);
+    }
+    line.truncate(0);
+  }
+}
+
+#[cfg(test)]
+mod tests_metrics {
+  use std::time::{Duration, UNIX_EPOCH};
+
+  #[test]
+  fn exposition_test() {
+    let mut metrics = super::Metrics::new(
+      vec!["hosts_blocked.txt".to_string(), "https://example.com/\"list\"".to_string()],
+      2,
+      &crate::statistics::Statistics::new(),
+      UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+      Duration::from_millis(1500),
+    );
+    metrics.record("10.0.0.30", Some(1));
+    metrics.record("10.0.0.30", None);
+    metrics.record("10.0.0.12", None);
+
+    let mut out = Vec::new();
+    metrics.write_metrics(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("dns_block_queries_total{client=\"10.0.0.30\"} 2\n"));
+    assert!(out.contains("dns_block_blocked_total{client=\"10.0.0.12\"} 0\n"));
+    assert!(out.contains("dns_block_blocked_by_source_total{source=\"https://example.com/\\\"list\\\"\"} 1\n"));
+    assert!(out.contains("dns_block_blocked_by_source_total{source=\"hosts_blocked.txt\"} 0\n"));
+    assert!(out.contains("dns_block_blocklist_domains 2\n"));
+    assert!(out.contains("dns_block_list_entries{kind=\"blocked\"} 0\n"));
+    assert!(out.contains("dns_block_last_pack_timestamp_seconds 1700000000\n"));
+    assert!(out.contains("dns_block_last_pack_duration_seconds 1.5\n"));
+  }
+}
@@ -0,0 +1,210 @@
+use crate::filter::{is_domain_blocked, open_input, parse_ip_filter, parse_query_line};
+use crate::sub_domains::Blacklist;
+use fnv::FnvHashMap as HashMap;
+use log::*;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// ANSI sequence to clear the terminal and move the cursor home
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+#[derive(Debug, Default)]
+struct ClientCounters {
+  queries: usize,
+  blocked: usize,
+}
+
+/// Rolling counters over the query log
+#[derive(Debug, Default)]
+pub struct Report {
+  queries: HashMap<String, usize>,
+  blocked: HashMap<String, usize>,
+  clients: HashMap<String, ClientCounters>,
+  total: usize,
+  total_blocked: usize,
+}
+
+impl Report {
+  pub fn new() -> Report {
+    Report::default()
+  }
+
+  pub fn record(&mut self, client: &str, domain: &str, blocked: bool) {
+    self.total += 1;
+    increment(&mut self.queries, domain);
+    let counters = match self.clients.get_mut(client) {
+      Some(counters) => counters,
+      None => self.clients.entry(client.to_string()).or_default(),
+    };
+    counters.queries += 1;
+    if blocked {
+      self.total_blocked += 1;
+      counters.blocked += 1;
+      increment(&mut self.blocked, domain);
+    }
+  }
+
+  pub fn write_summary(&self, out: &mut impl Write, top: usize) -> io::Result<()> {
+    writeln!(
+      out,
+      "Queries: {:>9}   Blocked: {:>9} {:>6.2}%",
+      self.total,
+      self.total_blocked,
+      pct(self.total_blocked, self.total)
+    )?;
+
+    writeln!(out, "\nTop queried domains")?;
+    for (domain, count) in top_n(&self.queries, top, |c| *c) {
+      writeln!(out, "{:>9} {}", count, domain)?;
+    }
+
+    writeln!(out, "\nTop blocked domains")?;
+    for (domain, count) in top_n(&self.blocked, top, |c| *c) {
+      writeln!(out, "{:>9} {}", count, domain)?;
+    }
+
+    writeln!(out, "\nTop clients")?;
+    writeln!(out, "{:>9} {:>9} {:>7} client", "queries", "blocked", "ratio")?;
+    for (client, counters) in top_n(&self.clients, top, |c| c.queries) {
+      writeln!(
+        out,
+        "{:>9} {:>9} {:>6.2}% {}",
+        counters.queries,
+        counters.blocked,
+        pct(counters.blocked, counters.queries),
+        client
+      )?;
+    }
+    Ok(())
+  }
+}
+
+fn increment(map: &mut HashMap<String, usize>, key: &str) {
+  match map.get_mut(key) {
+    Some(count) => *count += 1,
+    None => {
+      map.insert(key.to_string(), 1);
+    }
+  }
+}
+
+fn pct(x: usize, total: usize) -> f32 {
+  if total == 0 { 0.0 } else { x as f32 * 100.0 / total as f32 }
+}
+
+/// Sorts descending by count, ties alphabetically so the output is stable
+fn top_n<V>(map: &HashMap<String, V>, n: usize, count: fn(&V) -> usize) -> Vec<(&str, &V)> {
+  let mut entries: Vec<(&str, &V)> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+  entries.sort_unstable_by(|a, b| count(b.1).cmp(&count(a.1)).then_with(|| a.0.cmp(b.0)));
+  entries.truncate(n);
+  entries
+}
+
+/// Consumes the query log and prints the aggregated counters
+/// refresh, when specified, redraws the summary every that many seconds
+pub fn report(
+  blacklist_com: &Blacklist,
+  blacklist_net: &Blacklist,
+  filter_parameter: Option<&str>,
+  input_file: Option<&Path>,
+  top: usize,
+  refresh: Option<u64>,
+) -> io::Result<()> {
+  debug!("Report for client ips: {:#?}", filter_parameter);
+  let ip_filter = parse_ip_filter(filter_parameter);
+  let mut input = open_input(input_file)?;
+  let mut line = String::new();
+
+  let stdout = io::stdout();
+  let mut handle = stdout.lock();
+
+  let mut report = Report::new();
+  let record = |report: &mut Report, line: &str| {
+    if let Some((client, domain)) = parse_query_line(line)
+      && (ip_filter.is_empty() || ip_filter.contains(&client))
+    {
+      report.record(client, domain, is_domain_blocked(domain, blacklist_com, blacklist_net));
+    }
+  };
+
+  match refresh.map(Duration::from_secs) {
+    None => loop {
+      let n = input.read_line(&mut line)?;
+      if n == 0 {
+        break;
+      }
+      record(&mut report, &line);
+      line.truncate(0);
+    },
+    Some(interval) => {
+      // The lines are read on their own thread so a quiet log does not hold back the redraw
+      let (tx, rx) = mpsc::channel::<io::Result<String>>();
+      thread::spawn(move || {
+        for line in input.lines() {
+          let failed = line.is_err();
+          if tx.send(line).is_err() || failed {
+            return;
+          }
+        }
+      });
+      let mut next_refresh = Instant::now() + interval;
+      loop {
+        match rx.recv_timeout(next_refresh.saturating_duration_since(Instant::now())) {
+          Ok(line) => record(&mut report, &line?),
+          Err(RecvTimeoutError::Timeout) => {}
+          Err(RecvTimeoutError::Disconnected) => break,
+        }
+        // a busy log never times out, the deadline is checked after every line
+        if Instant::now() >= next_refresh {
+          handle.write_all(CLEAR_SCREEN.as_bytes())?;
+          report.write_summary(&mut handle, top)?;
+          handle.flush()?;
+          next_refresh = Instant::now() + interval;
+        }
+      }
+    }
+  }
+
+  if refresh.is_some() {
+    handle.write_all(CLEAR_SCREEN.as_bytes())?;
+  }
+  report.write_summary(&mut handle, top)?;
+  handle.flush()
+}
+
+#[cfg(test)]
+mod tests_report {
+  #[test]
+  fn summary_test() {
+    let mut report = super::Report::new();
+    report.record("10.0.0.30", "ads.example.com", true);
+    report.record("10.0.0.30", "ads.example.com", true);
+    report.record("10.0.0.30", "mydomain.com", false);
+    report.record("10.0.0.31", "mydomain.com", false);
+    report.record("10.0.0.31", "other.com", false);
+
+    let mut out = Vec::new();
+    report.write_summary(&mut out, 2).unwrap();
+    assert_eq!(
+      indoc::indoc! {"
+        Queries:         5   Blocked:         2  40.00%
+
+        Top queried domains
+                2 ads.example.com
+                2 mydomain.com
+
+        Top blocked domains
+                2 ads.example.com
+
+        Top clients
+          queries   blocked   ratio client
+                3         2  66.67% 10.0.0.30
+                2         0   0.00% 10.0.0.31
+      "},
+      String::from_utf8(out).unwrap()
+    );
+  }
+}
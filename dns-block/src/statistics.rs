@@ -1,4 +1,11 @@
+use crate::sub_domains::Blacklist;
+use shared::SourceOptions;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::fmt::Write;
+
+/// Tag of the domains from block lists without a tag option
+pub const UNTAGGED: &str = "untagged";
 
 #[derive(Debug)]
 pub struct Statistics {
@@ -37,6 +44,17 @@ impl Statistics {
     self.blocked += 1;
   }
 
+  /// The counters with a label for each, e.g. for exporting them as metrics
+  pub fn counters(&self) -> [(&'static str, usize); 5] {
+    [
+      ("subdomains", self.parent),
+      ("duplicates", self.duplicate),
+      ("whitelisted", self.whitelisted),
+      ("distinct_whitelisted", self.distinct_whitelisted),
+      ("blocked", self.blocked),
+    ]
+  }
+
   pub fn aggregate(stat1: &Statistics, stat2: &Statistics) -> Statistics {
     Statistics {
       parent: stat1.parent + stat2.parent,
@@ -76,6 +94,28 @@ impl fmt::Display for Statistics {
   }
 }
 
+/// Counts the blocked domains per tag of the block list that got them into the index
+pub fn blocked_by_tag<'a>(
+  index_com: &Blacklist,
+  index_net: &Blacklist,
+  source_options: &'a [SourceOptions],
+) -> BTreeMap<&'a str, usize> {
+  let mut tags = BTreeMap::new();
+  for entry in index_com.values().chain(index_net.values()) {
+    let tag = source_options.get(entry.source as usize).and_then(|options| options.tag.as_deref()).unwrap_or(UNTAGGED);
+    *tags.entry(tag).or_insert(0) += 1;
+  }
+  tags
+}
+
+pub fn format_tags(tags: &BTreeMap<&str, usize>) -> String {
+  let mut out = String::new();
+  for (tag, count) in tags {
+    writeln!(out, "{:<12} {:>7}", format!("{}:", tag), count).unwrap();
+  }
+  out
+}
+
 #[cfg(test)]
 mod tests_display {
 
@@ -96,3 +136,23 @@ mod tests_display {
     );
   }
 }
+
+#[cfg(test)]
+mod tests_tags {
+  use crate::sub_domains::{Blacklist, BlockEntry};
+  use shared::SourceOptions;
+
+  #[test]
+  fn blocked_by_tag_test() {
+    let options = vec![SourceOptions::default(), SourceOptions { tag: Some("malware".to_string()), ..SourceOptions::default() }];
+    let mut index_com = Blacklist::default();
+    index_com.insert("ads.example.com", BlockEntry { distinct: true, source: 0 });
+    index_com.insert("evil.example.com", BlockEntry { distinct: true, source: 1 });
+    let mut index_net = Blacklist::default();
+    index_net.insert("evil.example.net", BlockEntry { distinct: false, source: 1 });
+
+    let tags = super::blocked_by_tag(&index_com, &index_net, &options);
+    assert_eq!(vec![("malware", 2), ("untagged", 1)], tags.iter().map(|(t, c)| (*t, *c)).collect::<Vec<_>>());
+    assert_eq!("malware:           2\nuntagged:          1\n", super::format_tags(&tags));
+  }
+}
@@ -1,5 +1,7 @@
 use addr::parse_dns_name;
+use fnv::FnvHashMap as HashMap;
 use log::warn;
+use shared::ListFormat;
 
 pub fn count_char_occurences(line: &str, chr: char) -> usize {
   line.chars().filter(|c| *c == chr).count()
@@ -9,33 +11,46 @@ pub fn count_char_occurences(line: &str, chr: char) -> usize {
 pub struct Domain<'a> {
   pub name: &'a str,
   pub dots: usize,
+  // index of the block list the domain was read from
+  pub source: u32,
 }
 
+/// Value stored in the index of blocked domains
+#[derive(Debug, Clone, Copy)]
+pub struct BlockEntry {
+  // false if subdomains of this domain were also encountered
+  pub distinct: bool,
+  // index of the block list that got the domain into the index
+  pub source: u32,
+}
+
+pub type Blacklist<'a> = HashMap<&'a str, BlockEntry>;
+
 impl<'a> Domain<'a> {
-  pub fn new(line: &str) -> Option<Domain<'_>> {
-    let comment_stripped = match line.find('#') {
-      Some(idx) => &line[0..idx],
-      None => line,
-    }
-    .trim();
-    if let Some(name) = comment_stripped.split_whitespace().next_back() {
-      let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric());
-      if !name.is_empty() {
-        match parse_dns_name(name) {
-          Ok(_) => {}
-          Err(_) => {
-            warn!("Invalid domain name: 「{}」", name);
-            warn!("Original line: 「{line}」");
-            return None;
-          }
-        }
-        let dots = count_char_occurences(name, '.');
-        if dots > 0 {
-          return Some(Domain { name, dots });
-        }
+  /// Reads a line of a plain or hosts style list, like the allow list
+  pub fn new(line: &'a str) -> Option<Domain<'a>> {
+    let mut domain = None;
+    ListFormat::Plain.domains(line, |name| domain = Domain::from_name(name, line, 0));
+    domain
+  }
+
+  /// Calls found with every valid domain on a line of a block list
+  pub fn parse_line(line: &'a str, format: ListFormat, source: u32, mut found: impl FnMut(Domain<'a>)) {
+    format.domains(line, |name| {
+      if let Some(domain) = Domain::from_name(name, line, source) {
+        found(domain);
       }
+    });
+  }
+
+  fn from_name(name: &'a str, line: &str, source: u32) -> Option<Domain<'a>> {
+    if parse_dns_name(name).is_err() {
+      warn!("Invalid domain name: 「{}」", name);
+      warn!("Original line: 「{line}」");
+      return None;
     }
-    None
+    let dots = count_char_occurences(name, '.');
+    if dots > 0 { Some(Domain { name, dots, source }) } else { None }
   }
 }
 
@@ -70,3 +85,13 @@ fn domain_constructor_test() {
     assert_eq!(1, d.dots);
   }
 }
+
+#[test]
+fn parse_line_test() {
+  let mut found = Vec::new();
+  Domain::parse_line("address=/ads.example.com/tracker.example.net/0.0.0.0", ListFormat::Dnsmasq, 3, |d| found.push(d));
+  Domain::parse_line("||metrics.example.org^", ListFormat::Adblock, 4, |d| found.push(d));
+  Domain::parse_line("0.0.0.0 localhost", ListFormat::Hosts, 5, |d| found.push(d));
+  let names: Vec<(&str, u32)> = found.iter().map(|d| (d.name, d.source)).collect();
+  assert_eq!(vec![("ads.example.com", 3), ("tracker.example.net", 3), ("metrics.example.org", 4)], names);
+}
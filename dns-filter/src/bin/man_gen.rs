@@ -5,10 +5,40 @@ use shared::{ManExample, generate_man_page};
 fn main() -> std::io::Result<()> {
   // 1. Build the command from the struct
   let cmd = Args::command();
-  let examples = vec![ManExample {
-    title: "Substitute the client ip addresse using /etc/bind9/zones/db.0.0.10",
-    example: "cat dnsquery.log | dns-filter /etc/bind9/zones",
-  }];
+  let examples = vec![
+    ManExample {
+      title: "Substitute the client ip addresse using /etc/bind9/zones/db.0.0.10",
+      example: "cat dnsquery.log | dns-filter -m /etc/bind9/zones",
+    },
+    ManExample {
+      title: "Substitute the addresses of a /22, a guest network and IPv6 ULA clients",
+      example: "cat dnsquery.log | dns-filter -m /etc/bind9/zones -s 10.0.0.0/22 192.168.50.0/24 fd00::/64",
+    },
+    ManExample {
+      title: "Name the clients after the orgncf configuration and the dynamic leases",
+      example: "tail -f dnsquery.log | dns-filter --ncf lan.ncf --leases /var/lib/dhcp/dhcpd.leases",
+    },
+    ManExample {
+      title: "Name the clients after the Kea leases of both address families",
+      example: "tail -f dnsquery.log | dns-filter -m /etc/bind9/zones --leases /var/lib/kea/kea-leases4.csv /var/lib/kea/kea-leases6.csv",
+    },
+    ManExample {
+      title: "Keep the address next to the name and tag the LAN addresses nobody named, in color",
+      example: "tail -f dnsquery.log | dns-filter --ncf lan.ncf -s 10.0.0.0/22 --template '{name}({ip})' --tag-unknown --color always",
+    },
+    ManExample {
+      title: "Name the hosts of the firewall log, the senders by ip and by MAC address",
+      example: "journalctl -k -f | dns-filter --ncf lan.ncf --preset nftables --template '{name}({ip})'",
+    },
+    ManExample {
+      title: "Turn the dhcpd log into JSON objects for jq",
+      example: "tail -f /var/log/syslog | dns-filter --ncf lan.ncf --leases /var/lib/dhcp/dhcpd.leases --preset dhcpd --json | jq .",
+    },
+    ManExample {
+      title: "Load the names again after regenerating the zones, needed with --no-watch or for $INCLUDE files",
+      example: "pkill -HUP dns-filter",
+    },
+  ];
   generate_man_page(cmd, examples)?;
 
   Ok(())
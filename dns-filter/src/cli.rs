@@ -1,27 +1,106 @@
 use std::path::PathBuf;
 
-use clap::{Parser, ValueHint};
+use clap::{ArgGroup, Parser, ValueHint};
+
+use crate::enrich::Preset;
+use crate::rewrite::{ColorChoice, Rewrite, Template};
+use crate::subnet::Subnet;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about)]
+#[command(group(ArgGroup::new("names").required(true).multiple(true).args(["mapping_file_dir", "ncf", "forward_zone", "leases"])))]
 pub struct Args {
   /// log level, dddd for trace, ddd for debug, dd for info, d for warn, default no output
   #[arg(short, long, action = clap::ArgAction::Count)]
   pub debug: u8,
 
-  #[arg(short, long, required = false, default_value = "10.0.0", value_parser = validate_ipv4_prefix)]
-  pub subnet_prefix: String,
+  #[command(flatten)]
+  pub names: NameArgs,
+
+  /// Log format whose address fields are named, the other addresses in the line stay as they are,
+  /// without a preset every address is named
+  #[arg(short, long, value_enum)]
+  pub preset: Option<Preset>,
+
+  /// Writes a JSON object per line, with the line, the fields of the preset and the name and MAC address of every
+  /// address field
+  #[arg(short, long)]
+  pub json: bool,
+
+  #[command(flatten)]
+  pub rewrite: RewriteArgs,
+}
+
+/// Where the names of the devices come from, shared with dns-block pipe
+#[derive(clap::Args, Debug, Clone)]
+pub struct NameArgs {
+  /// Subnets whose reverse zones are read from the mapping file directory, in CIDR notation like 10.0.0.0/22
+  /// or fd00::/64, or the leading octets like 10.0.0, multiple can be specified
+  #[arg(short, long = "subnet", visible_alias = "subnet-prefix", num_args = 1.., value_delimiter = ' ', default_value = "10.0.0")]
+  pub subnets: Vec<Subnet>,
+
+  /// Directory containing the reverse zones, db.0.0.10 for 10.0.0.0/24, db.<zone>.ip6.arpa for IPv6
+  #[arg(short, long, value_hint = ValueHint::DirPath)]
+  pub mapping_file_dir: Option<PathBuf>,
+
+  /// orgncf configuration file naming the devices by ip and MAC address
+  #[arg(short, long, value_hint = ValueHint::FilePath)]
+  pub ncf: Option<PathBuf>,
+
+  /// Forward zone file, its A and AAAA records name the devices
+  #[arg(long, value_hint = ValueHint::FilePath)]
+  pub forward_zone: Option<PathBuf>,
+
+  /// Origin of the forward zone, completes the relative names in it
+  #[arg(short, long, default_value = "", requires = "forward_zone")]
+  pub origin: String,
+
+  /// ISC dhcpd.leases or Kea lease CSV files naming the devices that got a dynamic address, multiple can be specified
+  #[arg(short, long, num_args = 1.., value_delimiter = ' ', value_hint = ValueHint::FilePath)]
+  pub leases: Vec<PathBuf>,
 
-  /// Directory containing the mapping file. File itself will be db.<subnet_prefix reversed>
-  #[arg(short, long, required = false, value_hint = ValueHint::DirPath, default_value = "concatenated.list")]
-  pub mapping_file_dir: PathBuf,
+  /// Don't watch the files for changes, the names are then only loaded again on SIGHUP
+  #[arg(long)]
+  pub no_watch: bool,
+}
+
+impl NameArgs {
+  /// No source of names was given
+  pub fn is_empty(&self) -> bool {
+    self.mapping_file_dir.is_none() && self.ncf.is_none() && self.forward_zone.is_none() && self.leases.is_empty()
+  }
+}
+
+/// How the named addresses are written
+#[derive(clap::Args, Debug, Clone)]
+pub struct RewriteArgs {
+  /// How a known address is rewritten, {name} is the host name, {ip} the address as logged
+  /// and {mac} the MAC address if known, e.g. '{name}({ip})' or '{ip} [{name}]'
+  #[arg(short, long, default_value = "{name}")]
+  pub template: Template,
+
+  /// Tags the addresses inside the subnets that have no name, so devices missing from the configuration stand out,
+  /// {ip} is the address and {name} is unknown
+  #[arg(short = 'u', long, num_args = 0..=1, default_missing_value = "{ip}(unknown)")]
+  pub tag_unknown: Option<Template>,
+
+  /// Highlights the known hosts in green and the unknown ones in red
+  #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+  pub color: ColorChoice,
+}
+
+impl RewriteArgs {
+  /// Unknown addresses are looked for in the subnets of the names
+  pub fn rewrite(self, names: &NameArgs) -> Rewrite {
+    Rewrite { template: self.template, unknown: self.tag_unknown, subnets: names.subnets.clone(), color: self.color.enabled() }
+  }
 }
 
 pub fn get_args() -> Args {
   Args::parse()
 }
 
-fn validate_ipv4_prefix(s: &str) -> Result<String, String> {
+pub(crate) fn validate_ipv4_prefix(s: &str) -> Result<String, String> {
   let parts: Vec<&str> = s.split('.').collect();
   let error = Err(format!("「{s}」 should be three numbers separated by dots in the range 0-255"));
   if parts.len() != 3 {
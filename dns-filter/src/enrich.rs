@@ -0,0 +1,302 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde_json::{Map, Value, json};
+
+use crate::names::{Host, NameMap};
+use crate::rewrite::Rewrite;
+
+/// The logs with known fields, every other line has all its addresses rewritten
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+  /// BIND query log, client 10.0.0.5#53211 (example.com): query: example.com IN A + (10.0.0.1)
+  BindQuery,
+  /// BIND security log, denied queries and RPZ rewrites
+  BindSecurity,
+  /// ISC dhcpd syslog lines, DHCPACK on 10.0.0.5 to aa:bb:cc:dd:ee:ff (phone) via eth0
+  Dhcpd,
+  /// nftables and iptables kernel log lines, SRC= and DST= and the MAC= of the sender
+  Nftables,
+  /// nginx access log in the combined format
+  Nginx,
+}
+
+/// Regular expressions whose named groups are the fields of the log, every expression is tried on every line
+struct Pattern {
+  regexes: Vec<Regex>,
+  /// Fields holding an ip or MAC address, these are named
+  addresses: &'static [&'static str],
+}
+
+impl Pattern {
+  fn new(regexes: &[&str], addresses: &'static [&'static str]) -> Pattern {
+    let regexes = regexes.iter().map(|regex| Regex::new(regex).expect("valid preset regex")).collect();
+    Pattern { regexes, addresses }
+  }
+}
+
+const IPV4: &str = r"\d{1,3}(?:\.\d{1,3}){3}";
+const IP: &str = r"[0-9A-Fa-f:.]*[0-9A-Fa-f]";
+const MAC: &str = r"[0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5}";
+/// BIND prefixes the client with the address of its internal structure, client @0x7f2c8c0a1b68 10.0.0.5#53211
+const BIND_CLIENT: &str = r"client (?:@\S+ )?(?P<client>IP)#(?P<port>\d+)";
+
+static BIND_QUERY: LazyLock<Pattern> = LazyLock::new(|| {
+  Pattern::new(
+    &[
+      &BIND_CLIENT.replace("IP", IP),
+      &format!(r"query: (?P<query>\S+) (?P<class>\S+) (?P<type>\S+) (?P<flags>\S+) \((?P<server>{})\)", IP),
+    ],
+    &["client", "server"],
+  )
+});
+
+static BIND_SECURITY: LazyLock<Pattern> = LazyLock::new(|| {
+  Pattern::new(
+    &[
+      &BIND_CLIENT.replace("IP", IP),
+      r"'(?P<query>[^/']+)/(?P<type>[^/']+)/[^']+' (?P<action>denied|approved)",
+      r"rpz (?P<rpz>\S+ \S+) rewrite (?P<query>[^/\s]+)/(?P<type>[^/\s]+)",
+    ],
+    &["client"],
+  )
+});
+
+static DHCPD: LazyLock<Pattern> = LazyLock::new(|| {
+  Pattern::new(
+    &[
+      r"(?P<message>DHCP[A-Z]+)",
+      &format!(r"DHCP[A-Z]+ (?:on|for|of|from) (?P<ip>{})", IPV4),
+      &format!(r"\((?P<server>{})\) from", IPV4),
+      &format!(r"(?:to|from) (?P<mac>{})(?: \((?P<hostname>[^)]*)\))?", MAC),
+      r"via (?P<interface>\S+)",
+    ],
+    &["ip", "server", "mac"],
+  )
+});
+
+static NFTABLES: LazyLock<Pattern> = LazyLock::new(|| {
+  Pattern::new(
+    &[
+      r"IN=(?P<in>\S*) OUT=(?P<out>\S*)",
+      // MAC= is the destination, the source and the ethertype, only the source is a device of ours
+      &format!(r"MAC={}:(?P<src_mac>{})", MAC, MAC),
+      r"SRC=(?P<src>\S+) DST=(?P<dst>\S+)",
+      r"PROTO=(?P<proto>\S+)",
+      r"SPT=(?P<spt>\d+) DPT=(?P<dpt>\d+)",
+    ],
+    &["src_mac", "src", "dst"],
+  )
+});
+
+static NGINX: LazyLock<Pattern> = LazyLock::new(|| {
+  Pattern::new(
+    &[concat!(
+      r#"^(?P<client>\S+) \S+ (?P<user>\S+) \[(?P<time>[^\]]+)\] "(?P<request>[^"]*)" (?P<status>\d{3}) (?P<bytes>\d+|-)"#,
+      r#"(?: "(?P<referer>[^"]*)" "(?P<agent>[^"]*)")?"#
+    )],
+    &["client"],
+  )
+});
+
+impl Preset {
+  fn pattern(self) -> &'static Pattern {
+    match self {
+      Preset::BindQuery => &BIND_QUERY,
+      Preset::BindSecurity => &BIND_SECURITY,
+      Preset::Dhcpd => &DHCPD,
+      Preset::Nftables => &NFTABLES,
+      Preset::Nginx => &NGINX,
+    }
+  }
+
+  /// The fields found in the line, the first expression finding a field wins
+  pub fn fields(self, line: &str) -> Vec<Field<'_>> {
+    let pattern = self.pattern();
+    let mut fields: Vec<Field> = Vec::new();
+    for regex in &pattern.regexes {
+      let Some(caps) = regex.captures(line) else {
+        continue;
+      };
+      for name in regex.capture_names().flatten() {
+        if let Some(found) = caps.name(name)
+          && !fields.iter().any(|field| field.name == name)
+        {
+          let address = pattern.addresses.contains(&name);
+          fields.push(Field { name, range: found.range(), value: found.as_str(), address });
+        }
+      }
+    }
+    fields
+  }
+}
+
+/// A field of a log line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field<'a> {
+  pub name: &'static str,
+  pub range: Range<usize>,
+  pub value: &'a str,
+  pub address: bool,
+}
+
+/// Turns the log lines into lines naming the hosts, or into JSON objects with a field per part of the line
+#[derive(Debug, Clone, Default)]
+pub struct Enricher {
+  pub preset: Option<Preset>,
+  pub rewrite: Rewrite,
+  pub json: bool,
+}
+
+impl Enricher {
+  pub fn enrich<'a>(&self, names: &NameMap, line: &'a str) -> Cow<'a, str> {
+    match (self.preset, self.json) {
+      (None, false) => names.rewrite(line, &self.rewrite),
+      (None, true) => {
+        let addresses: Vec<Value> = names.addresses(line).map(|host| self.host_json(&host)).collect();
+        Cow::Owned(json!({ "line": line, "addresses": addresses }).to_string())
+      }
+      (Some(preset), false) => self.rewrite_fields(names, line, &preset.fields(line)),
+      (Some(preset), true) => {
+        let mut object = Map::new();
+        object.insert("line".to_string(), Value::from(line));
+        for field in preset.fields(line) {
+          let value = if field.address { self.host_json(&names.lookup(field.value)) } else { Value::from(field.value) };
+          object.insert(field.name.to_string(), value);
+        }
+        Cow::Owned(Value::Object(object).to_string())
+      }
+    }
+  }
+
+  /// Only the address fields are rewritten, the addresses in the rest of the line stay as they are
+  fn rewrite_fields<'a>(&self, names: &NameMap, line: &'a str, fields: &[Field]) -> Cow<'a, str> {
+    let mut addresses: Vec<&Field> = fields.iter().filter(|field| field.address).collect();
+    if addresses.is_empty() {
+      return Cow::Borrowed(line);
+    }
+    addresses.sort_by_key(|field| field.range.start);
+    let mut out = String::with_capacity(line.len() + 32);
+    let mut end = 0;
+    for field in addresses {
+      // the expressions of a preset may find the same address twice
+      if field.range.start < end {
+        continue;
+      }
+      out.push_str(&line[end..field.range.start]);
+      out.push_str(&self.rewrite.apply(&names.lookup(field.value)));
+      end = field.range.end;
+    }
+    out.push_str(&line[end..]);
+    Cow::Owned(out)
+  }
+
+  fn host_json(&self, host: &Host) -> Value {
+    let mut object = Map::new();
+    object.insert("address".to_string(), Value::from(host.address));
+    if let Some(name) = host.name {
+      object.insert("name".to_string(), Value::from(name));
+    }
+    if let Some(mac) = host.mac {
+      object.insert("mac".to_string(), Value::from(mac));
+    }
+    if self.rewrite.is_unknown(host) {
+      object.insert("unknown".to_string(), Value::from(true));
+    }
+    Value::Object(object)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::{Enricher, Preset};
+  use crate::names::NameMap;
+  use crate::rewrite::Rewrite;
+
+  fn names() -> NameMap {
+    let mut names = NameMap::default();
+    names.insert_ip("10.0.0.5".parse().unwrap(), "phone.lan".to_string());
+    names.insert_ip("10.0.0.1".parse().unwrap(), "router.lan".to_string());
+    names.insert_mac("aa:bb:cc:dd:ee:05", "phone.lan".to_string());
+    names.insert_mac_for_ip("10.0.0.5".parse().unwrap(), "aa:bb:cc:dd:ee:05");
+    names
+  }
+
+  fn enricher(preset: Preset) -> Enricher {
+    let rewrite = Rewrite { template: "{name}({ip})".parse().unwrap(), ..Rewrite::default() };
+    Enricher { preset: Some(preset), rewrite, json: false }
+  }
+
+  #[test]
+  fn bind_test() {
+    let line = "20-Jan-2021 10:10:10.536 client @0x7f2c 10.0.0.5#7216 (example.com): query: example.com IN A + (10.0.0.1)";
+    assert_eq!(
+      "20-Jan-2021 10:10:10.536 client @0x7f2c phone.lan(10.0.0.5)#7216 (example.com): query: example.com IN A + (router.lan(10.0.0.1))",
+      enricher(Preset::BindQuery).enrich(&names(), line)
+    );
+    let line = "client @0x7f2c 10.0.0.9#5353 (ads.example.com): query (cache) 'ads.example.com/A/IN' denied";
+    let fields = Preset::BindSecurity.fields(line);
+    assert_eq!(
+      vec![("client", "10.0.0.9"), ("port", "5353"), ("query", "ads.example.com"), ("type", "A"), ("action", "denied")],
+      fields.iter().map(|field| (field.name, field.value)).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn dhcpd_test() {
+    let line = "dhcpd[812]: DHCPREQUEST for 10.0.0.5 (10.0.0.1) from aa:bb:cc:dd:ee:05 (phone) via eth0";
+    assert_eq!(
+      "dhcpd[812]: DHCPREQUEST for phone.lan(10.0.0.5) (router.lan(10.0.0.1)) from phone.lan(aa:bb:cc:dd:ee:05) (phone) via eth0",
+      enricher(Preset::Dhcpd).enrich(&names(), line)
+    );
+    let line = "dhcpd[812]: DHCPDISCOVER from aa:bb:cc:dd:ee:09 via eth0";
+    let fields = Preset::Dhcpd.fields(line);
+    assert_eq!(vec!["message", "mac", "interface"], fields.iter().map(|field| field.name).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn nftables_json_test() {
+    let line = "kernel: drop IN=eth0 OUT=ppp0 MAC=00:11:22:33:44:55:aa:bb:cc:dd:ee:05:08:00 SRC=10.0.0.5 DST=8.8.8.8 \
+                LEN=60 PROTO=TCP SPT=40000 DPT=443";
+    let enricher = Enricher { json: true, ..enricher(Preset::Nftables) };
+    let value: serde_json::Value = serde_json::from_str(&enricher.enrich(&names(), line)).unwrap();
+    assert_eq!(json!({"address": "10.0.0.5", "name": "phone.lan", "mac": "aa:bb:cc:dd:ee:05"}), value["src"]);
+    assert_eq!(json!({"address": "aa:bb:cc:dd:ee:05", "name": "phone.lan", "mac": "aa:bb:cc:dd:ee:05"}), value["src_mac"]);
+    assert_eq!(json!({"address": "8.8.8.8"}), value["dst"]);
+    assert_eq!("443", value["dpt"]);
+    assert_eq!(line, value["line"]);
+  }
+
+  #[test]
+  fn nginx_test() {
+    let line = r#"10.0.0.5 - - [19/Oct/2026:10:00:00 +0000] "GET /10.0.0.1 HTTP/1.1" 200 612 "-" "curl/8.5.0""#;
+    // the address in the request is not a client
+    assert_eq!(
+      r#"phone.lan(10.0.0.5) - - [19/Oct/2026:10:00:00 +0000] "GET /10.0.0.1 HTTP/1.1" 200 612 "-" "curl/8.5.0""#,
+      enricher(Preset::Nginx).enrich(&names(), line)
+    );
+    assert_eq!("curl/8.5.0", Preset::Nginx.fields(line).iter().find(|field| field.name == "agent").unwrap().value);
+  }
+
+  #[test]
+  fn generic_json_test() {
+    let enricher = Enricher { json: true, ..Enricher::default() };
+    let value: serde_json::Value = serde_json::from_str(&enricher.enrich(&names(), "from 10.0.0.5 to 10.0.0.7")).unwrap();
+    assert_eq!(
+      json!([{"address": "10.0.0.5", "name": "phone.lan", "mac": "aa:bb:cc:dd:ee:05"}, {"address": "10.0.0.7"}]),
+      value["addresses"]
+    );
+    // neither the time nor the query id are addresses
+    let line = "20-Jan-2021 10:10:10.536 client @0x7f2c 10.0.0.5#7216 (example.com): query: example.com IN AAAA + (fd00::1)";
+    let value: serde_json::Value = serde_json::from_str(&enricher.enrich(&names(), line)).unwrap();
+    assert_eq!(
+      json!([{"address": "10.0.0.5", "name": "phone.lan", "mac": "aa:bb:cc:dd:ee:05"}, {"address": "fd00::1"}]),
+      value["addresses"]
+    );
+  }
+}
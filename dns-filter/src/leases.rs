@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::NaiveDateTime;
+
+/// A dynamic address handed out by the DHCP server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lease {
+  pub ip: IpAddr,
+  pub mac: Option<String>,
+  pub hostname: String,
+}
+
+/// The active leases of a file, dhcpd.leases and Kea CSV files are told apart by their content
+pub fn read_leases(path: &Path) -> io::Result<Vec<Lease>> {
+  let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() as i64);
+  Ok(parse_leases(&fs::read_to_string(path)?, now))
+}
+
+pub fn parse_leases(content: &str, now: i64) -> Vec<Lease> {
+  if content.trim_start().starts_with("address,") { parse_kea_leases(content, now) } else { parse_dhcpd_leases(content, now) }
+}
+
+/// Reads an ISC dhcpd.leases file, the file is a journal so the last entry for an address wins
+/// leases that are no longer active or have ended and leases without a client hostname are left out
+pub fn parse_dhcpd_leases(content: &str, now: i64) -> Vec<Lease> {
+  let mut leases: HashMap<IpAddr, Option<Lease>> = HashMap::new();
+  let mut current: Option<(IpAddr, Option<String>, Option<String>, bool)> = None;
+  for line in content.lines() {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    let line = line.strip_suffix(';').unwrap_or(line);
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    match tokens.as_slice() {
+      ["lease", ip, "{"] => current = ip.parse().ok().map(|ip| (ip, None, None, true)),
+      ["hardware", "ethernet", mac] => {
+        if let Some((_, current_mac, _, _)) = &mut current {
+          *current_mac = Some(mac.to_ascii_lowercase());
+        }
+      }
+      ["client-hostname", hostname] => {
+        if let Some((_, _, current_hostname, _)) = &mut current {
+          *current_hostname = Some(hostname.trim_matches('"').to_string());
+        }
+      }
+      ["binding", "state", state] => {
+        if let Some((_, _, _, active)) = &mut current {
+          *active &= *state == "active";
+        }
+      }
+      // ends 3 2026/10/14 22:00:00 in UTC, or ends epoch 1791986400 with db-time-format local, ends never is forever
+      ["ends", "epoch", seconds] => {
+        if let Some((_, _, _, active)) = &mut current {
+          *active &= seconds.parse::<i64>().is_ok_and(|end| end > now);
+        }
+      }
+      ["ends", _, date, time] => {
+        if let Some((_, _, _, active)) = &mut current {
+          let end = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y/%m/%d %H:%M:%S");
+          *active &= end.is_ok_and(|end| end.and_utc().timestamp() > now);
+        }
+      }
+      ["}"] => {
+        if let Some((ip, mac, hostname, active)) = current.take() {
+          let lease = hostname.filter(|_| active).map(|hostname| Lease { ip, mac, hostname });
+          leases.insert(ip, lease);
+        }
+      }
+      _ => {}
+    }
+  }
+  sorted(leases)
+}
+
+/// Reads a Kea memfile lease CSV, lease4 or lease6, the columns are found by the header
+/// the file is a journal too, the last row for an address wins, only state 0 leases that did not expire are active
+pub fn parse_kea_leases(content: &str, now: i64) -> Vec<Lease> {
+  let mut leases: HashMap<IpAddr, Option<Lease>> = HashMap::new();
+  let mut lines = content.lines();
+  let Some(header) = lines.next() else {
+    return Vec::new();
+  };
+  let columns: Vec<&str> = header.split(',').collect();
+  let column = |name: &str| columns.iter().position(|column| *column == name);
+  let (Some(address), Some(expire), Some(hostname)) = (column("address"), column("expire"), column("hostname")) else {
+    return Vec::new();
+  };
+  let (hwaddr, state) = (column("hwaddr"), column("state"));
+  for line in lines.filter(|line| !line.is_empty() && *line != header) {
+    let fields: Vec<&str> = line.split(',').collect();
+    let field = |index: Option<usize>| index.and_then(|index| fields.get(index)).copied().unwrap_or_default();
+    let Ok(ip) = field(Some(address)).parse() else {
+      continue;
+    };
+    let active = matches!(field(state), "" | "0") && field(Some(expire)).parse::<i64>().is_ok_and(|expire| expire > now);
+    // commas in the hostname are escaped by Kea
+    let name = field(Some(hostname)).replace("&#x2c", ",");
+    let name = name.trim_end_matches('.');
+    let mac = Some(field(hwaddr)).filter(|mac| !mac.is_empty()).map(str::to_ascii_lowercase);
+    let lease = (active && !name.is_empty()).then(|| Lease { ip, mac, hostname: name.to_string() });
+    leases.insert(ip, lease);
+  }
+  sorted(leases)
+}
+
+fn sorted(leases: HashMap<IpAddr, Option<Lease>>) -> Vec<Lease> {
+  let mut leases: Vec<Lease> = leases.into_values().flatten().collect();
+  leases.sort_by_key(|lease| lease.ip);
+  leases
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dhcpd_leases_test() {
+    let content = indoc::indoc! {r#"
+      # The format of this file is documented in the dhcpd.leases(5) manual page.
+      lease 10.0.0.150 {
+        starts 3 2026/10/14 10:00:00;
+        binding state active;
+        hardware ethernet AA:BB:CC:DD:EE:01;
+        client-hostname "phone";
+      }
+      lease 10.0.0.151 {
+        binding state active;
+        hardware ethernet aa:bb:cc:dd:ee:02;
+        client-hostname "tablet";
+      }
+      lease 10.0.0.151 {
+        binding state free;
+        hardware ethernet aa:bb:cc:dd:ee:02;
+      }
+      lease 10.0.0.152 {
+        binding state active;
+        hardware ethernet aa:bb:cc:dd:ee:03;
+      }
+      lease 10.0.0.153 {
+        ends 3 2026/10/14 10:00:00;
+        binding state active;
+        client-hostname "ended";
+      }
+    "#};
+    let now = NaiveDateTime::parse_from_str("2026/10/14 12:00:00", "%Y/%m/%d %H:%M:%S").unwrap().and_utc().timestamp();
+    assert_eq!(
+      vec![Lease {
+        ip: "10.0.0.150".parse().unwrap(),
+        mac: Some("aa:bb:cc:dd:ee:01".to_string()),
+        hostname: "phone".to_string()
+      }],
+      parse_leases(content, now)
+    );
+  }
+
+  #[test]
+  fn kea_leases_test() {
+    let content = indoc::indoc! {"
+      address,hwaddr,client_id,valid_lifetime,expire,subnet_id,fqdn_fwd,fqdn_rev,hostname,state,user_context,pool_id
+      10.0.0.160,AA:BB:CC:DD:EE:10,,3600,2000,1,0,0,phone.lan.,0,,0
+      10.0.0.161,aa:bb:cc:dd:ee:11,,3600,2000,1,0,0,tablet,0,,0
+      10.0.0.161,aa:bb:cc:dd:ee:11,,3600,2000,1,0,0,tablet,2,,0
+      10.0.0.162,aa:bb:cc:dd:ee:12,,3600,500,1,0,0,expired,0,,0
+    "};
+    assert_eq!(
+      vec![Lease {
+        ip: "10.0.0.160".parse().unwrap(),
+        mac: Some("aa:bb:cc:dd:ee:10".to_string()),
+        hostname: "phone.lan".to_string()
+      }],
+      parse_leases(content, 1000)
+    );
+  }
+}
@@ -1 +1,7 @@
 pub mod cli;
+pub mod enrich;
+pub mod leases;
+pub mod names;
+pub mod rewrite;
+pub mod sources;
+pub mod subnet;
@@ -1,53 +1,31 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, Write};
 
-mod cli;
+use dns_filter_lib::cli;
+use dns_filter_lib::enrich::Enricher;
+use dns_filter_lib::sources::LiveNames;
 
 fn main() -> io::Result<()> {
   let args = cli::get_args();
-  // 1. Configuration: Set your subnet prefix here
-  let mut ip_map = HashMap::new();
+  shared::setup_logging(args.debug);
 
-  // 2. Parse the PTR records from db.0.0.0
-  let mapping_file =
-    File::open(args.mapping_file_dir.join(format!("db.{}", args.subnet_prefix.split('.').rev().collect::<Vec<_>>().join("."))))?;
+  // 1. Collect the names from the orgncf configuration, the zones and the leases,
+  // they are loaded again in the background when the files change or on SIGHUP
+  let mut names = LiveNames::start(&args.names)?;
 
-  let reader = BufReader::new(mapping_file);
+  let mut rewrite = args.rewrite.rewrite(&args.names);
+  // escape sequences have no place in JSON
+  rewrite.color &= !args.json;
+  let enricher = Enricher { preset: args.preset, rewrite, json: args.json };
 
-  for line in reader.lines() {
-    let line = line?;
-    let parts: Vec<&str> = line.split_whitespace().collect();
-
-    // Typical PTR format: [octet] IN PTR [hostname]
-    if let Some(ptr_idx) = parts.iter().position(|&r| r == "PTR")
-      && ptr_idx > 0
-      && parts.len() > ptr_idx + 1
-    {
-      let last_octet = parts[0];
-      let hostname = parts[ptr_idx + 1].trim_end_matches('.');
-      let full_ip = format!("{}.{}", &args.subnet_prefix, last_octet);
-
-      ip_map.insert(full_ip, hostname.to_string());
-    }
-  }
-
-  // 3. Setup Stream Processing
-  let ip_regex = Regex::new(r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})").unwrap();
+  // 2. Setup Stream Processing
   let stdin = io::stdin();
   let stdout = io::stdout();
   let mut handle = stdout.lock();
 
   for line in stdin.lock().lines() {
     let line = line?;
-    let result = ip_regex.replace_all(&line, |caps: &regex::Captures| {
-      let ip = &caps[0];
-      // If the IP is in our PTR map, swap it; otherwise, keep the IP
-      ip_map.get(ip).cloned().unwrap_or_else(|| ip.to_string())
-    });
-
-    writeln!(handle, "{}", result)?;
+    // If the address is in our map, swap it; otherwise, keep the address unless it is an unknown one to tag
+    writeln!(handle, "{}", enricher.enrich(names.current(), &line))?;
   }
 
   Ok(())
@@ -0,0 +1,251 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use log::warn;
+use regex::{Captures, Regex};
+use shared::{ZoneError, read_zone, reverse_name_to_ip};
+
+use crate::leases::Lease;
+use crate::rewrite::Rewrite;
+use crate::subnet::Subnet;
+
+/// Anything looking like an address, IPv6 and MAC addresses share the colon notation with times like 10:10:10.536,
+/// the whole colon token is taken and only kept when it is one of the two
+static ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"[0-9A-Fa-f.]*(?::[0-9A-Fa-f.]*){2,}|\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}").expect("valid address regex")
+});
+
+/// Six hex pairs, e.g. aa:bb:cc:dd:ee:05
+static MAC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9A-Fa-f]{2}(?::[0-9A-Fa-f]{2}){5}$").expect("valid MAC regex"));
+
+/// Host names of the devices on the LAN by ip and by MAC address
+/// every source can be added, the first source naming an address wins
+#[derive(Debug, Default, Clone)]
+pub struct NameMap {
+  by_ip: HashMap<IpAddr, String>,
+  by_mac: HashMap<String, String>,
+  /// MAC addresses of the devices the configuration or the leases tie to an ip
+  mac_by_ip: HashMap<IpAddr, String>,
+}
+
+/// What is known about an address found in a log line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Host<'a> {
+  /// The address as it was logged
+  pub address: &'a str,
+  pub ip: Option<IpAddr>,
+  pub name: Option<&'a str>,
+  pub mac: Option<&'a str>,
+}
+
+impl NameMap {
+  pub fn len(&self) -> usize {
+    self.by_ip.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.by_ip.is_empty()
+  }
+
+  pub fn insert_ip(&mut self, ip: IpAddr, name: String) {
+    if let Entry::Vacant(entry) = self.by_ip.entry(ip.to_canonical()) {
+      entry.insert(name);
+    }
+  }
+
+  pub fn insert_mac(&mut self, mac: &str, name: String) {
+    if let Entry::Vacant(entry) = self.by_mac.entry(normalize_mac(mac)) {
+      entry.insert(name);
+    }
+  }
+
+  /// IPv4 addresses mapped into IPv6 are found under their IPv4 form
+  pub fn name_for_ip(&self, ip: IpAddr) -> Option<&str> {
+    self.by_ip.get(&ip.to_canonical()).map(String::as_str)
+  }
+
+  pub fn name_for_mac(&self, mac: &str) -> Option<&str> {
+    self.by_mac.get(&normalize_mac(mac)).map(String::as_str)
+  }
+
+  pub fn insert_mac_for_ip(&mut self, ip: IpAddr, mac: &str) {
+    if let Entry::Vacant(entry) = self.mac_by_ip.entry(ip.to_canonical()) {
+      entry.insert(normalize_mac(mac));
+    }
+  }
+
+  pub fn mac_for_ip(&self, ip: IpAddr) -> Option<&str> {
+    self.mac_by_ip.get(&ip.to_canonical()).map(String::as_str)
+  }
+
+  /// An ip or a MAC address, None for anything else
+  pub fn find<'a>(&'a self, address: &'a str) -> Option<Host<'a>> {
+    if let Ok(ip) = address.parse::<IpAddr>() {
+      Some(Host { address, ip: Some(ip), name: self.name_for_ip(ip), mac: self.mac_for_ip(ip) })
+    } else if MAC.is_match(address) {
+      Some(Host { address, ip: None, name: self.name_for_mac(address), mac: Some(address) })
+    } else {
+      None
+    }
+  }
+
+  /// Like find, a value that is not an address is kept as it is
+  pub fn lookup<'a>(&'a self, address: &'a str) -> Host<'a> {
+    self.find(address).unwrap_or(Host { address, ip: None, name: None, mac: None })
+  }
+
+  /// Every device of an orgncf configuration, named name.domain as in the generated zones
+  pub fn add_ncf(&mut self, content: &str) -> Result<(), String> {
+    let parsed_info = orgncf_generator::parser::parser(content).map_err(|e| e.to_string())?;
+    let prefix = orgncf_generator::ipv6::prefix(&parsed_info);
+    for line in &parsed_info.ip_lines {
+      let name = match line.names.first() {
+        Some(&"@") | None => parsed_info.domain.to_string(),
+        Some(name) => format!("{}.{}", name, parsed_info.domain),
+      };
+      let ip = line.ip.parse().ok();
+      match ip {
+        Some(ip) => self.insert_ip(ip, name.clone()),
+        None => warn!("Ignoring the invalid ip address 「{}」 on line {}", line.ip, line.number),
+      }
+      let ipv6 = orgncf_generator::ipv6::addresses(line, prefix.as_ref());
+      for ip6 in &ipv6 {
+        self.insert_ip(IpAddr::V6(*ip6), name.clone());
+      }
+      if let Some(mac) = line.mac {
+        for ip in ip.into_iter().chain(ipv6.into_iter().map(IpAddr::V6)) {
+          self.insert_mac_for_ip(ip, mac);
+        }
+        self.insert_mac(mac, name);
+      }
+    }
+    Ok(())
+  }
+
+  /// The A and AAAA records of a forward zone, relative names are completed with the origin
+  pub fn add_forward_zone(&mut self, path: &Path, origin: &str) -> Result<(), ZoneError> {
+    for record in read_zone(path, origin)? {
+      if (record.rtype == "A" || record.rtype == "AAAA")
+        && let Some(ip) = record.data.first().and_then(|ip| ip.parse().ok())
+      {
+        self.insert_ip(ip, record.name);
+      }
+    }
+    Ok(())
+  }
+
+  /// The PTR records of the reverse zones covering the subnets, e.g. db.0.0.10 for 10.0.0.0/24
+  /// returns how many zone files were found, missing zones are skipped
+  pub fn add_reverse_zones(&mut self, dir: &Path, subnets: &[Subnet]) -> io::Result<usize> {
+    let mut zones_read = 0;
+    for subnet in subnets {
+      for zone in subnet.reverse_zones() {
+        let zone_file = Subnet::zone_file(dir, &zone);
+        let records = match read_zone(&zone_file, &zone) {
+          Ok(records) => records,
+          // a /22 may well be served from fewer than four zones
+          Err(ZoneError::Read { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+            warn!("No reverse zone 「{}」 for {}", zone_file.display(), subnet);
+            continue;
+          }
+          Err(e) => return Err(io::Error::other(e)),
+        };
+        zones_read += 1;
+        for record in records.into_iter().filter(|record| record.rtype == "PTR") {
+          if let Some(ip) = reverse_name_to_ip(&record.name).filter(|ip| subnet.contains(*ip))
+            && let Some(hostname) = record.data.into_iter().next()
+          {
+            self.insert_ip(ip, hostname);
+          }
+        }
+      }
+    }
+    Ok(zones_read)
+  }
+
+  pub fn add_leases(&mut self, leases: Vec<Lease>) {
+    for lease in leases {
+      if let Some(mac) = &lease.mac {
+        self.insert_mac_for_ip(lease.ip, mac);
+        self.insert_mac(mac, lease.hostname.clone());
+      }
+      self.insert_ip(lease.ip, lease.hostname);
+    }
+  }
+
+  /// Replaces the known ip and MAC addresses in a log line with the host names
+  pub fn substitute<'a>(&self, line: &'a str) -> Cow<'a, str> {
+    self.rewrite(line, &Rewrite::default())
+  }
+
+  /// Rewrites the ip and MAC addresses in a log line as the rewrite says
+  pub fn rewrite<'a>(&self, line: &'a str, rewrite: &Rewrite) -> Cow<'a, str> {
+    ADDRESS.replace_all(line, |caps: &Captures| match self.find(&caps[0]) {
+      Some(host) => rewrite.apply(&host),
+      None => caps[0].to_string(),
+    })
+  }
+
+  /// Every address in a log line, for the lines no preset knows
+  pub fn addresses<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Host<'a>> {
+    ADDRESS.find_iter(line).filter_map(|found| self.find(found.as_str()))
+  }
+}
+
+/// 10-00-00-00-00-AA and 10:00:00:00:00:aa are the same device
+fn normalize_mac(mac: &str) -> String {
+  mac.to_ascii_lowercase().replace('-', ":")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::NameMap;
+
+  #[test]
+  fn ncf_test() {
+    let ncf = indoc::indoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 router gw
+      10.0.0.2 @
+      10-00-00-00-00-BB 10.0.0.4 printer
+      domain lan
+      dns_file_name db.lan
+      reverse_dns_file_name db.0.0.10
+      dhcp_file_name reservations.conf
+      ipv6_prefix fd00::/64
+      reverse_ipv6_dns_file_name db.fd00
+      DNS_PREFIX """ """
+      DNS_SUFFIX """ """
+      DHCP_PREFIX """ """
+    "#};
+    let mut names = NameMap::default();
+    names.add_ncf(ncf).unwrap();
+    assert_eq!(4, names.len());
+    assert_eq!(Some("router.lan"), names.name_for_ip("fd00::1".parse().unwrap()));
+    assert_eq!(Some("10:00:00:00:00:aa"), names.mac_for_ip("fd00::1".parse().unwrap()));
+    assert_eq!(Some("printer.lan"), names.name_for_mac("10:00:00:00:00:bb"));
+    assert_eq!(Some("10:00:00:00:00:bb"), names.mac_for_ip("10.0.0.4".parse().unwrap()));
+    assert_eq!(
+      "client router.lan#53 lan 10.0.0.3 DHCPACK to router.lan (x) at 10:12:13.456",
+      names.substitute("client 10.0.0.1#53 10.0.0.2 10.0.0.3 DHCPACK to 10:00:00:00:00:AA (x) at 10:12:13.456")
+    );
+    // an address mapped into IPv6 is still found
+    assert_eq!(Some("lan"), names.name_for_ip("::ffff:10.0.0.2".parse().unwrap()));
+  }
+
+  #[test]
+  fn forward_zone_test() {
+    let path = std::env::temp_dir().join(format!("dns-filter-forward-test-{}", std::process::id()));
+    std::fs::write(&path, "$TTL 1h\nrouter IN A 10.0.0.1\ngw IN CNAME router\nnas A 10.0.0.2\n AAAA fd00::2\n").unwrap();
+    let mut names = NameMap::default();
+    // names already known keep their first name
+    names.insert_ip("10.0.0.2".parse().unwrap(), "storage.lan".to_string());
+    names.add_forward_zone(&path, "lan").unwrap();
+    assert_eq!("router.lan storage.lan nas.lan", names.substitute("10.0.0.1 10.0.0.2 fd00::2"));
+    std::fs::remove_file(path).unwrap();
+  }
+}
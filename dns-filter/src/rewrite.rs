@@ -0,0 +1,171 @@
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+use crate::names::Host;
+use crate::subnet::Subnet;
+
+/// Known hosts are green
+const KNOWN_COLOR: &str = "\x1b[32m";
+/// Unknown hosts on the LAN are bold red
+const UNKNOWN_COLOR: &str = "\x1b[1;31m";
+const RESET_COLOR: &str = "\x1b[0m";
+/// Stands in for the name of an unknown host
+const UNKNOWN_NAME: &str = "unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+  /// Color when writing to a terminal
+  Auto,
+  Always,
+  Never,
+}
+
+impl ColorChoice {
+  pub fn enabled(self) -> bool {
+    match self {
+      ColorChoice::Auto => io::stdout().is_terminal(),
+      ColorChoice::Always => true,
+      ColorChoice::Never => false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+  Text(String),
+  Name,
+  Ip,
+  Mac,
+}
+
+/// Replacement for an address, e.g. {name}({ip}), with {name} the host name, {ip} the address as it was logged
+/// and {mac} the MAC address of the host, empty when it is not known
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+  parts: Vec<Part>,
+}
+
+impl Default for Template {
+  fn default() -> Template {
+    Template { parts: vec![Part::Name] }
+  }
+}
+
+impl FromStr for Template {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Template, String> {
+    let mut parts = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('{') {
+      let Some(end) = rest[start..].find('}') else {
+        return Err(format!("「{}」 has a {{ without a closing }}", s));
+      };
+      if start > 0 {
+        parts.push(Part::Text(rest[..start].to_string()));
+      }
+      parts.push(match &rest[start + 1..start + end] {
+        "name" => Part::Name,
+        "ip" => Part::Ip,
+        "mac" => Part::Mac,
+        other => return Err(format!("「{{{}}}」 is not a placeholder, use {{name}}, {{ip}} or {{mac}}", other)),
+      });
+      rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+      parts.push(Part::Text(rest.to_string()));
+    }
+    Ok(Template { parts })
+  }
+}
+
+impl Template {
+  pub fn render(&self, name: &str, host: &Host) -> String {
+    self
+      .parts
+      .iter()
+      .map(|part| match part {
+        Part::Text(text) => text.as_str(),
+        Part::Name => name,
+        Part::Ip => host.address,
+        Part::Mac => host.mac.unwrap_or_default(),
+      })
+      .collect()
+  }
+}
+
+/// How the addresses of a line are rewritten
+#[derive(Debug, Clone, Default)]
+pub struct Rewrite {
+  pub template: Template,
+  /// Tags the addresses of the LAN that have no name, so devices missing from the configuration stand out
+  pub unknown: Option<Template>,
+  /// The LAN, only its addresses are tagged as unknown
+  pub subnets: Vec<Subnet>,
+  pub color: bool,
+}
+
+impl Rewrite {
+  /// The replacement for an address found in the log, the address itself when it stays as is
+  pub fn apply(&self, host: &Host) -> String {
+    if let Some(name) = host.name {
+      return self.paint(KNOWN_COLOR, self.template.render(name, host));
+    }
+    match &self.unknown {
+      Some(unknown) if self.is_unknown(host) => self.paint(UNKNOWN_COLOR, unknown.render(UNKNOWN_NAME, host)),
+      _ => host.address.to_string(),
+    }
+  }
+
+  /// An address of the LAN without a name, only when tagging them was asked for
+  pub fn is_unknown(&self, host: &Host) -> bool {
+    self.unknown.is_some()
+      && host.name.is_none()
+      && host.ip.is_some_and(|ip| self.subnets.iter().any(|subnet| subnet.contains(ip.to_canonical())))
+  }
+
+  fn paint(&self, color: &str, text: String) -> String {
+    if self.color { format!("{}{}{}", color, text, RESET_COLOR) } else { text }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Rewrite, Template};
+  use crate::names::Host;
+
+  fn host<'a>(address: &'a str, name: Option<&'a str>) -> Host<'a> {
+    Host { address, ip: address.parse().ok(), name, mac: None }
+  }
+
+  #[test]
+  fn template_test() {
+    let template: Template = "{ip} [{name}] {mac}".parse().unwrap();
+    let router = Host { mac: Some("aa:bb:cc:dd:ee:ff"), ..host("10.0.0.1", None) };
+    assert_eq!("10.0.0.1 [router.lan] aa:bb:cc:dd:ee:ff", template.render("router.lan", &router));
+    assert_eq!("router.lan", Template::default().render("router.lan", &router));
+    assert!("{host}".parse::<Template>().is_err());
+    assert!("{name".parse::<Template>().is_err());
+  }
+
+  #[test]
+  fn rewrite_test() {
+    let rewrite = Rewrite {
+      template: "{name}({ip})".parse().unwrap(),
+      unknown: Some("{ip}(unknown)".parse().unwrap()),
+      subnets: vec!["10.0.0.0/24".parse().unwrap()],
+      color: false,
+    };
+    assert_eq!("router.lan(10.0.0.1)", rewrite.apply(&host("10.0.0.1", Some("router.lan"))));
+    assert_eq!("10.0.0.9(unknown)", rewrite.apply(&host("10.0.0.9", None)));
+    assert_eq!("::ffff:10.0.0.9(unknown)", rewrite.apply(&host("::ffff:10.0.0.9", None)));
+    // only the LAN is tagged
+    assert_eq!("8.8.8.8", rewrite.apply(&host("8.8.8.8", None)));
+    assert_eq!("aa:bb:cc:dd:ee:ff", rewrite.apply(&host("aa:bb:cc:dd:ee:ff", None)));
+
+    let colored = Rewrite { color: true, ..rewrite };
+    assert_eq!("\x1b[1;31m10.0.0.9(unknown)\x1b[0m", colored.apply(&host("10.0.0.9", None)));
+  }
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use log::{debug, info, warn};
+use signal_hook::consts::SIGHUP;
+
+use crate::cli::NameArgs;
+use crate::leases::read_leases;
+use crate::names::NameMap;
+use crate::subnet::Subnet;
+
+/// How often the files are checked, a change is only loaded once the files stayed the same for a whole check
+/// so a zone being rewritten by orgncf-generator is not read half way
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Every file the names are read from, loaded again when one of them changes or on SIGHUP
+#[derive(Debug, Clone)]
+pub struct NameSources {
+  ncf: Option<PathBuf>,
+  forward_zone: Option<PathBuf>,
+  origin: String,
+  mapping_file_dir: Option<PathBuf>,
+  subnets: Vec<Subnet>,
+  leases: Vec<PathBuf>,
+}
+
+impl NameSources {
+  pub fn new(args: &NameArgs) -> NameSources {
+    NameSources {
+      ncf: args.ncf.clone(),
+      forward_zone: args.forward_zone.clone(),
+      origin: args.origin.clone(),
+      mapping_file_dir: args.mapping_file_dir.clone(),
+      subnets: args.subnets.clone(),
+      leases: args.leases.clone(),
+    }
+  }
+
+  /// The orgncf configuration is the source of truth, the other sources fill the gaps
+  pub fn load(&self) -> io::Result<NameMap> {
+    let mut names = NameMap::default();
+    if let Some(ncf) = &self.ncf {
+      names.add_ncf(&fs::read_to_string(ncf)?).map_err(|e| io::Error::other(format!("「{}」: {}", ncf.display(), e)))?;
+    }
+    if let Some(forward_zone) = &self.forward_zone {
+      names.add_forward_zone(forward_zone, &self.origin).map_err(io::Error::other)?;
+    }
+    // the PTR records of the reverse zones, e.g. db.0.0.10 for 0.0.10.in-addr.arpa
+    if let Some(dir) = &self.mapping_file_dir
+      && names.add_reverse_zones(dir, &self.subnets)? == 0
+    {
+      return Err(io::Error::new(io::ErrorKind::NotFound, format!("no reverse zones found in 「{}」", dir.display())));
+    }
+    for path in &self.leases {
+      names.add_leases(read_leases(path).map_err(|e| io::Error::new(e.kind(), format!("「{}」: {}", path.display(), e)))?);
+    }
+    Ok(names)
+  }
+
+  /// The files the names come from, reverse zones that don't exist yet are watched too, files pulled in with
+  /// $INCLUDE are not, send SIGHUP after changing those
+  fn files(&self) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = self.ncf.iter().chain(&self.forward_zone).cloned().collect();
+    if let Some(dir) = &self.mapping_file_dir {
+      for subnet in &self.subnets {
+        files.extend(subnet.reverse_zones().iter().map(|zone| Subnet::zone_file(dir, zone)));
+      }
+    }
+    files.extend(self.leases.iter().cloned());
+    files
+  }
+
+  fn modified(&self) -> Vec<Option<SystemTime>> {
+    self.files().iter().map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok()).collect()
+  }
+
+  /// Loads the names again in the background whenever the files changed, or always on SIGHUP,
+  /// every complete set of names is sent, the names in use are kept when loading fails
+  pub fn watch(self, watch_files: bool) -> io::Result<Receiver<NameMap>> {
+    let hangup = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(SIGHUP, Arc::clone(&hangup))?;
+    let (sender, receiver) = mpsc::channel();
+    let mut loaded = self.modified();
+    let mut last = loaded.clone();
+    thread::spawn(move || {
+      loop {
+        thread::sleep(CHECK_INTERVAL);
+        let hangup = hangup.swap(false, Ordering::Relaxed);
+        let current = if watch_files || hangup { self.modified() } else { loaded.clone() };
+        let settled = current != loaded && current == last;
+        last = current;
+        if !hangup && !settled {
+          continue;
+        }
+        debug!("Loading the names again, {}", if hangup { "SIGHUP received" } else { "the files changed" });
+        // a file changing while being read is read again at the next check
+        loaded = last.clone();
+        match self.load() {
+          Ok(names) => {
+            info!("Names reloaded, found names for {} addresses", names.len());
+            if sender.send(names).is_err() {
+              return;
+            }
+          }
+          Err(e) => warn!("Could not load the names again, keeping the previous names: {}", e),
+        }
+      }
+    });
+    Ok(receiver)
+  }
+}
+
+/// The names in use while streaming, the names loaded again in the background take their place between two lines
+pub struct LiveNames {
+  names: NameMap,
+  reloaded: Receiver<NameMap>,
+}
+
+impl LiveNames {
+  pub fn start(args: &NameArgs) -> io::Result<LiveNames> {
+    let sources = NameSources::new(args);
+    let names = sources.load()?;
+    info!("Found names for {} addresses", names.len());
+    let reloaded = sources.watch(!args.no_watch)?;
+    Ok(LiveNames { names, reloaded })
+  }
+
+  /// The latest names, swapped as a whole, so no line is named from two versions
+  pub fn current(&mut self) -> &NameMap {
+    if let Some(latest) = self.reloaded.try_iter().last() {
+      self.names = latest;
+    }
+    &self.names
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::NameSources;
+  use crate::subnet::Subnet;
+
+  #[test]
+  fn sources_test() {
+    let dir = std::env::temp_dir().join(format!("dns-filter-sources-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let leases = dir.join("dhcpd.leases");
+    std::fs::write(&leases, "lease 10.0.0.150 {\n  client-hostname \"phone\";\n}\n").unwrap();
+    let sources = NameSources {
+      ncf: None,
+      forward_zone: None,
+      origin: String::new(),
+      mapping_file_dir: Some(dir.clone()),
+      subnets: vec!["10.0.0.0/23".parse::<Subnet>().unwrap()],
+      leases: vec![leases.clone()],
+    };
+    // a missing reverse zone is watched, it may be written later
+    assert_eq!(vec![dir.join("db.0.0.10"), dir.join("db.1.0.10"), leases.clone()], sources.files());
+    assert!(sources.load().is_err());
+
+    std::fs::write(dir.join("db.1.0.10"), "$ORIGIN 1.0.10.in-addr.arpa.\n5 PTR nas.lan.\n").unwrap();
+    let before = sources.modified();
+    let names = sources.load().unwrap();
+    assert_eq!("phone nas.lan", names.substitute("10.0.0.150 10.0.1.5"));
+    std::fs::remove_file(&leases).unwrap();
+    assert_ne!(before, sources.modified());
+    assert!(sources.load().unwrap_err().to_string().contains(&leases.display().to_string()));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
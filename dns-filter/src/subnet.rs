@@ -0,0 +1,154 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::cli::validate_ipv4_prefix;
+
+/// A network in CIDR notation, e.g. 10.0.0.0/22 or fd00::/64
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subnet {
+  network: IpAddr,
+  prefix_len: u8,
+}
+
+impl Subnet {
+  pub fn new(address: IpAddr, prefix_len: u8) -> Result<Subnet, String> {
+    let max = max_prefix_len(address);
+    if prefix_len > max {
+      return Err(format!("prefix length {} is longer than {} bits", prefix_len, max));
+    }
+    // the host bits are dropped, 10.0.0.1/24 is the same as 10.0.0.0/24
+    let network = from_bits(address, bits(address) & mask(prefix_len, max));
+    Ok(Subnet { network, prefix_len })
+  }
+
+  pub fn contains(&self, ip: IpAddr) -> bool {
+    let max = max_prefix_len(self.network);
+    max == max_prefix_len(ip) && bits(ip) & mask(self.prefix_len, max) == bits(self.network)
+  }
+
+  /// Names of the reverse zones covering the subnet, zones are cut on octet boundaries for IPv4 and on nibble
+  /// boundaries for IPv6, so a /22 is covered by four /24 zones
+  pub fn reverse_zones(&self) -> Vec<String> {
+    let (label_bits, suffix) = match self.network {
+      IpAddr::V4(_) => (8, "in-addr.arpa"),
+      IpAddr::V6(_) => (4, "ip6.arpa"),
+    };
+    let max = max_prefix_len(self.network) as u32;
+    let zone_len = (self.prefix_len as u32).div_ceil(label_bits) * label_bits;
+    (0..1u128 << (zone_len - self.prefix_len as u32))
+      .map(|i| {
+        let zone = bits(self.network) | (i << (max - zone_len));
+        let mut labels: Vec<String> = (0..zone_len / label_bits)
+          .map(|label| {
+            let value = (zone >> (max - (label + 1) * label_bits)) & ((1 << label_bits) - 1);
+            if label_bits == 8 { value.to_string() } else { format!("{:x}", value) }
+          })
+          .collect();
+        labels.reverse();
+        labels.push(suffix.to_string());
+        labels.join(".")
+      })
+      .collect()
+  }
+
+  /// File of a reverse zone, db.0.0.10 for 0.0.10.in-addr.arpa as written by orgncf-generator,
+  /// IPv6 zones keep their full name, e.g. db.0.0.d.f.ip6.arpa
+  pub fn zone_file(dir: &Path, zone: &str) -> PathBuf {
+    dir.join(format!("db.{}", zone.strip_suffix(".in-addr.arpa").unwrap_or(zone)))
+  }
+}
+
+impl fmt::Display for Subnet {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}/{}", self.network, self.prefix_len)
+  }
+}
+
+impl FromStr for Subnet {
+  type Err = String;
+
+  /// CIDR notation, a single address, or the leading octets of an IPv4 network like 10.0.0
+  fn from_str(s: &str) -> Result<Subnet, String> {
+    if let Some((address, prefix_len)) = s.split_once('/') {
+      let address = address.parse().map_err(|_| format!("「{}」 is not an ip address", address))?;
+      let prefix_len = prefix_len.parse().map_err(|_| format!("「{}」 is not a prefix length", prefix_len))?;
+      return Subnet::new(address, prefix_len);
+    }
+    if let Ok(address) = s.parse::<IpAddr>() {
+      return Subnet::new(address, max_prefix_len(address));
+    }
+    // the leading octets, the only form accepted before CIDR notation
+    let prefix = validate_ipv4_prefix(s)?;
+    let address = format!("{}.0", prefix).parse().map_err(|_| format!("「{}」 is not a subnet", s))?;
+    Subnet::new(address, 24)
+  }
+}
+
+fn max_prefix_len(address: IpAddr) -> u8 {
+  if address.is_ipv4() { 32 } else { 128 }
+}
+
+fn bits(address: IpAddr) -> u128 {
+  match address {
+    IpAddr::V4(address) => u32::from(address) as u128,
+    IpAddr::V6(address) => u128::from(address),
+  }
+}
+
+fn from_bits(like: IpAddr, bits: u128) -> IpAddr {
+  match like {
+    IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(bits as u32)),
+    IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(bits)),
+  }
+}
+
+fn mask(prefix_len: u8, max: u8) -> u128 {
+  let all = if max == 32 { u32::MAX as u128 } else { u128::MAX };
+  match prefix_len {
+    0 => 0,
+    _ if prefix_len >= max => all,
+    _ => all & !(all >> prefix_len),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use super::Subnet;
+
+  #[test]
+  fn parse_test() {
+    assert_eq!("10.0.0.0/24", "10.0.0".parse::<Subnet>().unwrap().to_string());
+    assert_eq!("10.0.0.0/22", "10.0.1.7/22".parse::<Subnet>().unwrap().to_string());
+    assert_eq!("fd00::/64", "fd00::1/64".parse::<Subnet>().unwrap().to_string());
+    assert_eq!("10.0.0.5/32", "10.0.0.5".parse::<Subnet>().unwrap().to_string());
+    assert_eq!("fd00::5/128", "fd00::5".parse::<Subnet>().unwrap().to_string());
+    assert!("10.0.0.0/33".parse::<Subnet>().is_err());
+    assert!("10.0".parse::<Subnet>().is_err());
+  }
+
+  #[test]
+  fn contains_test() {
+    let subnet: Subnet = "10.0.0.0/22".parse().unwrap();
+    assert!(subnet.contains("10.0.3.255".parse().unwrap()));
+    assert!(!subnet.contains("10.0.4.1".parse().unwrap()));
+    assert!(!subnet.contains("::a00:1".parse().unwrap()));
+    assert!("::/0".parse::<Subnet>().unwrap().contains("fd00::1".parse().unwrap()));
+  }
+
+  #[test]
+  fn reverse_zones_test() {
+    let subnet: Subnet = "10.0.0.0/22".parse().unwrap();
+    assert_eq!(
+      vec!["0.0.10.in-addr.arpa", "1.0.10.in-addr.arpa", "2.0.10.in-addr.arpa", "3.0.10.in-addr.arpa"],
+      subnet.reverse_zones()
+    );
+    assert_eq!(vec!["168.192.in-addr.arpa"], "192.168.0.0/16".parse::<Subnet>().unwrap().reverse_zones());
+    assert_eq!(vec!["0.0.0.0.0.0.0.0.0.0.0.0.0.0.d.f.ip6.arpa"], "fd00::/64".parse::<Subnet>().unwrap().reverse_zones());
+    assert_eq!(2, "fd00::/63".parse::<Subnet>().unwrap().reverse_zones().len());
+    assert_eq!(Path::new("/zones/db.0.0.10"), Subnet::zone_file(Path::new("/zones"), "0.0.10.in-addr.arpa"));
+  }
+}
@@ -0,0 +1,42 @@
+/// Create the DHCPv6 config file for dhcpd -6
+use std::{
+  fs::File,
+  io::{BufWriter, Write},
+};
+
+use crate::ipv6;
+use crate::parser::{Line, ParsedInfo};
+
+pub fn write_dhcpv6_config(
+  parsed_info: &ParsedInfo, output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  if parsed_info.dhcpv6_file_name.is_empty() {
+    return Ok(());
+  }
+  std::fs::create_dir_all(output_dir)?;
+  let mut path = std::path::PathBuf::from(output_dir);
+  path.push(parsed_info.dhcpv6_file_name);
+
+  let file = File::create(&path)?;
+  let mut out = BufWriter::new(&file);
+
+  let prefix = ipv6::prefix(parsed_info);
+  for line in &parsed_info.ip_lines {
+    let Line { mac, names, .. } = line;
+    let name = if names[0] == "@" {
+      parsed_info.domain
+    } else {
+      names[0]
+    };
+    // dhcpd takes a single fixed-address6 per host, the first address is reserved
+    if let (Some(mac_address), Some(ip)) = (mac, ipv6::addresses(line, prefix.as_ref()).first()) {
+      writeln!(out)?;
+      writeln!(out, "host {name} {{")?;
+      writeln!(out, "  hardware ethernet {};", mac_address)?;
+      writeln!(out, "  fixed-address6 {ip};")?;
+      writeln!(out, "}}")?;
+    }
+  }
+  println!("✓ DHCPv6 config written to 「{}」", path.display());
+  Ok(())
+}
@@ -2,8 +2,10 @@
 use std::{
   fs::File,
   io::{BufWriter, Write},
+  net::Ipv6Addr,
 };
 
+use crate::ipv6;
 use crate::parser::{Line, ParsedInfo};
 
 pub fn write_dns_config(
@@ -19,9 +21,11 @@ pub fn write_dns_config(
   writeln!(out, "{}", parsed_info.dns_prefix)?;
 
   let longest = compute_max_name_lenght(&parsed_info.ip_lines);
+  let prefix = ipv6::prefix(parsed_info);
   for line in &parsed_info.ip_lines {
     let Line { ip, names, .. } = line;
-    write_ip_group(&mut out, ip, names, longest)?;
+    let ipv6 = ipv6::addresses(line, prefix.as_ref());
+    write_ip_group(&mut out, ip, &ipv6, names, longest)?;
   }
 
   if !parsed_info.dns_suffix.is_empty() {
@@ -35,12 +39,15 @@ pub fn write_dns_config(
 }
 
 fn write_ip_group(
-  out: &mut BufWriter<&File>, ip: &str, names: &[&str], longest: usize,
+  out: &mut BufWriter<&File>, ip: &str, ipv6: &[Ipv6Addr], names: &[&str], longest: usize,
 ) -> Result<(), Box<dyn std::error::Error>> {
   let mut i = names.iter();
   let a = i.next().unwrap();
   let width = longest + 1;
   writeln!(out, "{a:width$} IN A     {ip}")?;
+  ipv6
+    .iter()
+    .try_for_each(|ip6| writeln!(out, "{a:width$} IN AAAA  {ip6}"))?;
   i.try_for_each(|name| writeln!(out, "{0:1$} IN CNAME {2}", name, longest + 1, a))?;
   Ok(())
 }
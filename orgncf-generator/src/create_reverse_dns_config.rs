@@ -1,17 +1,78 @@
-/// Create the reverse DNS config file for bind
+/// Create the reverse DNS config files for bind, one per zone
 use std::{
   fs::File,
   io::{BufWriter, Write},
+  net::Ipv4Addr,
 };
 
 use crate::parser::{Line, ParsedInfo};
+use crate::subnet::{ReverseZone, Subnet};
 
 pub fn write_reverse_dns_config(
   parsed_info: &ParsedInfo, output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  // without subnets all the hosts are in the same /24, named by the last octet
+  if parsed_info.subnets.is_empty() {
+    let lines: Vec<&Line> = parsed_info.ip_lines.iter().collect();
+    return write_zone(
+      parsed_info,
+      output_dir,
+      parsed_info.reverse_dns_file_name,
+      None,
+      &lines,
+      &[],
+    );
+  }
+  // the /24 zones the classless zones are cut from, each with the delegations to its classless zones
+  let mut parents: Vec<(ReverseZone, Vec<String>)> = Vec::new();
+  for subnet in parsed_info
+    .subnets
+    .iter()
+    .filter_map(|subnet| Subnet::parse(subnet))
+  {
+    for zone in subnet.zones() {
+      let lines: Vec<&Line> = parsed_info
+        .ip_lines
+        .iter()
+        .filter(|line| line.ip.parse().is_ok_and(|ip| zone.contains(ip)))
+        .collect();
+      write_zone(
+        parsed_info,
+        output_dir,
+        &zone.file_name(),
+        Some(&zone),
+        &lines,
+        &[],
+      )?;
+      if let (Some(parent), Some(delegation)) = (zone.parent(), zone.delegation()) {
+        match parents.iter_mut().find(|(known, _)| *known == parent) {
+          Some((_, delegations)) => delegations.push(delegation),
+          None => parents.push((parent, vec![delegation])),
+        }
+      }
+    }
+  }
+  for (parent, delegations) in &parents {
+    write_zone(
+      parsed_info,
+      output_dir,
+      &parent.file_name(),
+      Some(parent),
+      &[],
+      delegations,
+    )?;
+  }
+  Ok(())
+}
+
+/// Writes the PTR records of the lines, the records written before them are the delegations of a parent zone
+fn write_zone(
+  parsed_info: &ParsedInfo, output_dir: &str, file_name: &str, zone: Option<&ReverseZone>,
+  lines: &[&Line], records: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
   std::fs::create_dir_all(output_dir)?;
   let mut path = std::path::PathBuf::from(output_dir);
-  path.push(parsed_info.reverse_dns_file_name);
+  path.push(file_name);
 
   let file = File::create(&path)?;
   let mut out = BufWriter::new(&file);
@@ -19,10 +80,20 @@ pub fn write_reverse_dns_config(
   // write the prefix
   writeln!(out, "{}", parsed_info.dns_prefix)?;
 
-  for line in &parsed_info.ip_lines {
+  if let Some(zone) = zone {
+    writeln!(out, "$ORIGIN {}.", zone.name)?;
+  }
+  for record in records {
+    writeln!(out, "{record}")?;
+  }
+
+  for line in lines {
     let Line { ip, names, .. } = line;
     let name = names[0];
-    let addr = ip.rsplit('.').next().unwrap();
+    let addr = match (zone, ip.parse::<Ipv4Addr>()) {
+      (Some(zone), Ok(ip)) => zone.record_name(ip),
+      _ => ip.rsplit('.').next().unwrap().to_string(),
+    };
     if name == "@" {
       writeln!(out, "{addr:3} IN PTR {}.", parsed_info.domain)?;
     } else {
@@ -0,0 +1,47 @@
+/// Create the ip6.arpa reverse DNS config file for bind
+use std::{
+  fs::File,
+  io::{BufWriter, Write},
+};
+
+use crate::ipv6;
+use crate::parser::ParsedInfo;
+
+pub fn write_reverse_ipv6_dns_config(
+  parsed_info: &ParsedInfo, output_dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  if parsed_info.reverse_ipv6_dns_file_name.is_empty() {
+    return Ok(());
+  }
+  std::fs::create_dir_all(output_dir)?;
+  let mut path = std::path::PathBuf::from(output_dir);
+  path.push(parsed_info.reverse_ipv6_dns_file_name);
+
+  let file = File::create(&path)?;
+  let mut out = BufWriter::new(&file);
+
+  // write the prefix
+  writeln!(out, "{}", parsed_info.dns_prefix)?;
+
+  // the names are relative to the zone of the ipv6_prefix, the validation keeps every address in it
+  // and there are no addresses without one
+  if let Some(prefix) = ipv6::prefix(parsed_info) {
+    writeln!(out, "$ORIGIN {}.", prefix.zone())?;
+    for line in &parsed_info.ip_lines {
+      let name = line.names[0];
+      for ip in ipv6::addresses(line, Some(&prefix)) {
+        let addr = ipv6::reverse_name(ip, &prefix);
+        if name == "@" {
+          writeln!(out, "{addr} IN PTR {}.", parsed_info.domain)?;
+        } else {
+          writeln!(out, "{addr} IN PTR {name}.{}.", parsed_info.domain)?;
+        }
+      }
+    }
+  }
+  println!(
+    "✓ IPv6 reverse DNS config written to 「{}」",
+    path.display()
+  );
+  Ok(())
+}
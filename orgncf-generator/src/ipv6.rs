@@ -0,0 +1,99 @@
+/// IPv6 addresses of the host lines, written in full or as an interface ID, e.g. ::5, completed with the ipv6_prefix
+use std::net::Ipv6Addr;
+
+use crate::parser::{Line, ParsedInfo};
+
+/// The network the interface IDs are completed with, its ip6.arpa zone holds the PTR records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ipv6Prefix {
+  pub network: Ipv6Addr,
+  pub len: u8,
+}
+
+impl Ipv6Prefix {
+  /// A network like fd00:0:0:1::/64, the length has to fall on a nibble so the reverse zone has a name
+  pub fn parse(prefix: &str) -> Option<Ipv6Prefix> {
+    let (network, len) = prefix.split_once('/')?;
+    let network: Ipv6Addr = network.parse().ok()?;
+    let len: u8 = len.parse().ok()?;
+    if len > 128 || !len.is_multiple_of(4) || u128::from(network) & !mask(len) != 0 {
+      return None;
+    }
+    Some(Ipv6Prefix { network, len })
+  }
+
+  pub fn contains(&self, ip: Ipv6Addr) -> bool {
+    u128::from(ip) & mask(self.len) == u128::from(self.network)
+  }
+
+  /// The interface ID in the host bits of the network, None when it does not fit
+  pub fn complete(&self, interface_id: Ipv6Addr) -> Option<Ipv6Addr> {
+    let id = u128::from(interface_id);
+    (id & mask(self.len) == 0).then(|| Ipv6Addr::from(u128::from(self.network) | id))
+  }
+
+  /// e.g. 1.0.0.0.0.0.0.0.0.0.0.0.0.0.d.f.ip6.arpa for fd00:0:0:1::/64
+  pub fn zone(&self) -> String {
+    let nibbles = nibbles(self.network);
+    let mut zone: Vec<&str> = nibbles[..self.len as usize / 4]
+      .iter()
+      .rev()
+      .map(String::as_str)
+      .collect();
+    zone.push("ip6.arpa");
+    zone.join(".")
+  }
+}
+
+/// The upper len bits set
+fn mask(len: u8) -> u128 {
+  u128::MAX.checked_shl(128 - len as u32).unwrap_or(0)
+}
+
+/// The 32 hex digits of the address, most significant first
+fn nibbles(ip: Ipv6Addr) -> Vec<String> {
+  format!("{:032x}", u128::from(ip))
+    .chars()
+    .map(String::from)
+    .collect()
+}
+
+/// Written without the network part, it needs the ipv6_prefix
+pub fn is_interface_id(address: &str) -> bool {
+  address.starts_with("::")
+}
+
+/// The full address, None when it is not valid or is an interface ID without a prefix to complete it
+pub fn resolve(address: &str, prefix: Option<&Ipv6Prefix>) -> Option<Ipv6Addr> {
+  let ip: Ipv6Addr = address.parse().ok()?;
+  if is_interface_id(address) {
+    prefix?.complete(ip)
+  } else {
+    Some(ip)
+  }
+}
+
+/// The configured prefix, None when there is none or it is invalid, the validation reports the latter
+pub fn prefix(parsed_info: &ParsedInfo) -> Option<Ipv6Prefix> {
+  Ipv6Prefix::parse(parsed_info.ipv6_prefix)
+}
+
+/// The full IPv6 addresses of a host line
+pub fn addresses(line: &Line, prefix: Option<&Ipv6Prefix>) -> Vec<Ipv6Addr> {
+  line
+    .ipv6
+    .iter()
+    .filter_map(|address| resolve(address, prefix))
+    .collect()
+}
+
+/// Name of the PTR record relative to the zone of the prefix, the validation keeps the addresses in the prefix
+pub fn reverse_name(ip: Ipv6Addr, prefix: &Ipv6Prefix) -> String {
+  let nibbles = nibbles(ip);
+  let host: Vec<&str> = nibbles[prefix.len as usize / 4..]
+    .iter()
+    .rev()
+    .map(String::as_str)
+    .collect();
+  host.join(".")
+}
@@ -1,8 +1,12 @@
-mod parser;
+pub mod parser;
+pub mod ipv6;
+mod subnet;
 mod validation;
 mod create_dns_config;
 mod create_reverse_dns_config;
+mod create_reverse_ipv6_dns_config;
 mod create_dhcp_config;
+mod create_dhcpv6_config;
 
 pub fn process<'a>(input: &'a str, input_file_name: &'a str, output_dir: &'a str) -> Result<(), Box<dyn std::error::Error + 'a>> {
   let parsed_info = parser::parser(input)?;
@@ -12,7 +16,9 @@ pub fn process<'a>(input: &'a str, input_file_name: &'a str, output_dir: &'a str
   println!("✓ Validated configuration file 「{}」", input_file_name);
   create_dns_config::write_dns_config(&parsed_info, output_dir)?;
   create_reverse_dns_config::write_reverse_dns_config(&parsed_info, output_dir)?;
+  create_reverse_ipv6_dns_config::write_reverse_ipv6_dns_config(&parsed_info, output_dir)?;
   create_dhcp_config::write_dhcp_config(&parsed_info, output_dir)?;
+  create_dhcpv6_config::write_dhcpv6_config(&parsed_info, output_dir)?;
   Ok(())
 }
 
@@ -7,6 +7,8 @@ pub struct Line<'a> {
   pub _text: &'a str,
   pub mac: Option<&'a str>,
   pub ip: &'a str,
+  /// Full addresses or interface IDs, e.g. ::5, completed with the ipv6_prefix
+  pub ipv6: Vec<&'a str>,
   pub names: Vec<&'a str>,
 }
 
@@ -20,6 +22,11 @@ pub struct ParsedInfo<'a> {
   pub dns_file_name: &'a str,
   pub reverse_dns_file_name: &'a str,
   pub dhcp_file_name: &'a str,
+  pub ipv6_prefix: &'a str,
+  pub reverse_ipv6_dns_file_name: &'a str,
+  pub dhcpv6_file_name: &'a str,
+  /// The IPv4 networks of the LAN, each gets its reverse zones
+  pub subnets: Vec<&'a str>,
 }
 
 pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error + '_>> {
@@ -38,6 +45,10 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
   let mut dns_file_name = "";
   let mut reverse_dns_file_name = "";
   let mut dhcp_file_name = "";
+  let mut ipv6_prefix = "";
+  let mut reverse_ipv6_dns_file_name = "";
+  let mut dhcpv6_file_name = "";
+  let mut subnets = Vec::<&str>::new();
   let mut dns_prefix = "";
   let mut dns_suffix = "";
   let mut dhcp_prefix = "";
@@ -70,6 +81,7 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
 
           let mut mac: Option<&str> = None;
           let mut ip: &str = "";
+          let mut ipv6: Vec<&str> = Vec::new();
           let mut names: Vec<&str> = Vec::new();
 
           let mut line_cursor = node.walk();
@@ -79,6 +91,7 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
               match child_node.kind() {
                 "mac_address" => mac = Some(val(child_node)),
                 "ip_address" => ip = val(child_node),
+                "ipv6_address" => ipv6.push(val(child_node)),
                 "hostname" => {
                   let name = val(child_node);
                   names.push(name);
@@ -96,6 +109,7 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
             _text: line_text,
             mac,
             ip,
+            ipv6,
             names,
           });
         },
@@ -103,6 +117,10 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
         "dns_file_name" => dns_file_name = val_child(node, 1),
         "reverse_dns_file_name" => reverse_dns_file_name = val_child(node, 1),
         "dhcp_file_name" => dhcp_file_name = val_child(node, 1),
+        "ipv6_prefix" => ipv6_prefix = val_child(node, 1),
+        "reverse_ipv6_dns_file_name" => reverse_ipv6_dns_file_name = val_child(node, 1),
+        "dhcpv6_file_name" => dhcpv6_file_name = val_child(node, 1),
+        "subnet" => subnets.push(val_child(node, 1)),
         "dns_prefix_section" => dns_prefix = val_grandchild(node, 1, 1).trim_start(),
         "dns_suffix_section" => dns_suffix = val_grandchild(node, 1, 1).trim_start(),
         "dhcp_prefix_section" => dhcp_prefix = val_grandchild(node, 1, 1).trim_start(),
@@ -123,6 +141,10 @@ pub fn parser(content: &str) -> Result<ParsedInfo<'_>, Box<dyn std::error::Error
     dns_file_name,
     reverse_dns_file_name,
     dhcp_file_name,
+    ipv6_prefix,
+    reverse_ipv6_dns_file_name,
+    dhcpv6_file_name,
+    subnets,
   })
   //  println!("Parsed IP Lines: \n{:?}", ip_lines);
   //  println!("Parsed Domain: 「{}」", domain);
@@ -0,0 +1,140 @@
+/// IPv4 subnets of the LAN and the reverse zones holding their PTR records
+use std::net::Ipv4Addr;
+
+/// A declared subnet, e.g. 10.0.0.0/23
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Subnet {
+  network: u32,
+  len: u8,
+}
+
+/// A reverse zone, cut on an octet boundary like 0.0.10.in-addr.arpa, or classless as in RFC 2317 for the
+/// subnets smaller than a /24, like 128-191.1.168.192.in-addr.arpa
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReverseZone {
+  pub name: String,
+  network: u32,
+  len: u8,
+  classless: bool,
+}
+
+impl Subnet {
+  /// The host bits have to be 0, subnets larger than a /8 have no reverse zone
+  pub fn parse(subnet: &str) -> Option<Subnet> {
+    let (network, len) = subnet.split_once('/')?;
+    let network = u32::from(network.parse::<Ipv4Addr>().ok()?);
+    let len: u8 = len.parse().ok()?;
+    if !(8..=32).contains(&len) || network & !mask(len) != 0 {
+      return None;
+    }
+    Some(Subnet { network, len })
+  }
+
+  /// The subnet of the given length around the address, e.g. 10.0.0.0/24 for 10.0.0.5
+  pub fn containing(ip: Ipv4Addr, len: u8) -> Subnet {
+    Subnet {
+      network: u32::from(ip) & mask(len),
+      len,
+    }
+  }
+
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    u32::from(ip) & mask(self.len) == self.network
+  }
+
+  pub fn overlaps(&self, other: &Subnet) -> bool {
+    self.contains(Ipv4Addr::from(other.network)) || other.contains(Ipv4Addr::from(self.network))
+  }
+
+  /// A /23 is covered by two /24 zones, a /16 by a single zone, a /26 by a classless zone
+  pub fn zones(&self) -> Vec<ReverseZone> {
+    if self.len > 24 {
+      let last = self.network | !mask(self.len);
+      let octets = Ipv4Addr::from(self.network).octets();
+      let name = format!(
+        "{}-{}.{}.{}.{}.in-addr.arpa",
+        octets[3],
+        last & 0xff,
+        octets[2],
+        octets[1],
+        octets[0]
+      );
+      return vec![ReverseZone {
+        name,
+        network: self.network,
+        len: self.len,
+        classless: true,
+      }];
+    }
+    let zone_len = self.len.div_ceil(8) * 8;
+    (0..1u32 << (zone_len - self.len))
+      .map(|i| {
+        let network = self.network | (i << (32 - zone_len));
+        let octets = Ipv4Addr::from(network).octets();
+        let mut labels: Vec<String> = octets[..zone_len as usize / 8]
+          .iter()
+          .rev()
+          .map(u8::to_string)
+          .collect();
+        labels.push("in-addr.arpa".to_string());
+        ReverseZone {
+          name: labels.join("."),
+          network,
+          len: zone_len,
+          classless: false,
+        }
+      })
+      .collect()
+  }
+}
+
+impl ReverseZone {
+  pub fn contains(&self, ip: Ipv4Addr) -> bool {
+    u32::from(ip) & mask(self.len) == self.network
+  }
+
+  /// db.0.0.10 for 0.0.10.in-addr.arpa, the name dns-filter looks for
+  pub fn file_name(&self) -> String {
+    format!("db.{}", self.name.trim_end_matches(".in-addr.arpa"))
+  }
+
+  /// Name of the PTR record relative to the zone, the last octet in a classless zone
+  pub fn record_name(&self, ip: Ipv4Addr) -> String {
+    let octets = ip.octets();
+    let host = if self.classless {
+      &octets[3..]
+    } else {
+      &octets[self.len as usize / 8..]
+    };
+    let labels: Vec<String> = host.iter().rev().map(u8::to_string).collect();
+    labels.join(".")
+  }
+
+  /// The /24 zone a classless zone is cut from, e.g. 1.168.192.in-addr.arpa for 128-191.1.168.192.in-addr.arpa
+  pub fn parent(&self) -> Option<ReverseZone> {
+    if !self.classless {
+      return None;
+    }
+    let (_, parent) = self.name.split_once('.')?;
+    Some(ReverseZone {
+      name: parent.to_string(),
+      network: self.network & mask(24),
+      len: 24,
+      classless: false,
+    })
+  }
+
+  /// The CNAME records the parent zone needs to hand the addresses of a classless zone over to it
+  pub fn delegation(&self) -> Option<String> {
+    if !self.classless {
+      return None;
+    }
+    let (range, _) = self.name.split_once('.')?;
+    Some(format!("$GENERATE {range} $ IN CNAME $.{}.", self.name))
+  }
+}
+
+/// The upper len bits set
+fn mask(len: u8) -> u32 {
+  u32::MAX.checked_shl(32 - len as u32).unwrap_or(0)
+}
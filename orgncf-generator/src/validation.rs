@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
+use crate::ipv6::{self, Ipv6Prefix};
 use crate::parser::{Line, ParsedInfo};
+use crate::subnet::Subnet;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -19,11 +22,35 @@ pub enum ValidationError {
   MissingReverseDNSFileName(),
   #[error("✗ No DHCP file specified in the configuration (dhcp_file_name)")]
   MissingDHCPFileName(),
+  #[error("✗ Invalid IPv6 prefix {0}, the length must be a multiple of 4 (ipv6_prefix)")]
+  InvalidIpv6Prefix(String),
+  #[error("✗ Invalid IPv6 address {1} on line {0}")]
+  InvalidIpv6Address(usize, String),
+  #[error("✗ IPv6 address {1} on line {0} needs a prefix in the configuration (ipv6_prefix)")]
+  MissingIpv6Prefix(usize, String),
+  #[error("✗ IPv6 address {1} on line {0} is outside the prefix {2} (ipv6_prefix)")]
+  Ipv6AddressOutsidePrefix(usize, String, String),
+  #[error("✗ No IPv6 reverse DNS file specified in the configuration (reverse_ipv6_dns_file_name)")]
+  MissingReverseIPv6DNSFileName(),
+  #[error("✗ Invalid ip address {1} on line {0}")]
+  InvalidIpAddress(usize, String),
+  #[error("✗ Invalid subnet {0}, expected a network like 10.0.0.0/24 (subnet)")]
+  InvalidSubnet(String),
+  #[error("✗ Subnet {0} overlaps subnet {1}")]
+  OverlappingSubnets(String, String),
+  #[error("✗ Ip address {1} on line {0} is not in a declared subnet (subnet)")]
+  IpAddressOutsideSubnets(usize, String),
+  #[error("✗ Ip address {1} on line {0} is not in the /24 of line {2}, declare subnets (subnet)")]
+  IpAddressOutsideReverseZone(usize, String, usize),
+  #[error("✗ The reverse zones are named after the subnets, remove reverse_dns_file_name")]
+  ReverseDNSFileNameWithSubnets(),
 }
 
 pub fn validate(parsed_info: &ParsedInfo) -> Result<(), ValidationError> {
   check_unique_mac(parsed_info)?;
   check_unique_ip(parsed_info)?;
+  check_subnets(parsed_info)?;
+  check_ipv6(parsed_info)?;
   check_unique_host(parsed_info)?;
   check_required_fields(parsed_info)?;
   Ok(())
@@ -69,6 +96,100 @@ fn check_unique_ip(parsed_info: &ParsedInfo) -> Result<(), ValidationError> {
   Ok(())
 }
 
+/// Every ip address is in a declared subnet, without subnets they all have to share the /24 of the first one,
+/// the only reverse zone written then
+fn check_subnets(parsed_info: &ParsedInfo) -> Result<(), ValidationError> {
+  let mut subnets = Vec::<(&str, Subnet)>::new();
+  for declared in parsed_info.subnets.iter() {
+    let subnet = Subnet::parse(declared)
+      .ok_or_else(|| ValidationError::InvalidSubnet(declared.to_string()))?;
+    if let Some((other, _)) = subnets.iter().find(|(_, other)| other.overlaps(&subnet)) {
+      return Err(ValidationError::OverlappingSubnets(
+        declared.to_string(),
+        other.to_string(),
+      ));
+    }
+    subnets.push((declared, subnet));
+  }
+
+  let mut first: Option<(&Line, Subnet)> = None;
+  for ip_line in parsed_info.ip_lines.iter() {
+    let ip: Ipv4Addr = ip_line
+      .ip
+      .parse()
+      .map_err(|_| ValidationError::InvalidIpAddress(ip_line.number, ip_line.ip.to_string()))?;
+    if !subnets.is_empty() {
+      if !subnets.iter().any(|(_, subnet)| subnet.contains(ip)) {
+        return Err(ValidationError::IpAddressOutsideSubnets(
+          ip_line.number,
+          ip_line.ip.to_string(),
+        ));
+      }
+      continue;
+    }
+    match &first {
+      None => first = Some((ip_line, Subnet::containing(ip, 24))),
+      Some((first_line, zone)) if !zone.contains(ip) => {
+        return Err(ValidationError::IpAddressOutsideReverseZone(
+          ip_line.number,
+          ip_line.ip.to_string(),
+          first_line.number,
+        ));
+      },
+      Some(_) => {},
+    }
+  }
+  Ok(())
+}
+
+/// Every IPv6 address resolves to a full address and is used only once
+fn check_ipv6(parsed_info: &ParsedInfo) -> Result<(), ValidationError> {
+  let prefix = match parsed_info.ipv6_prefix {
+    "" => None,
+    prefix => Some(
+      Ipv6Prefix::parse(prefix)
+        .ok_or_else(|| ValidationError::InvalidIpv6Prefix(prefix.to_string()))?,
+    ),
+  };
+  let mut uniq = HashMap::<Ipv6Addr, &Line>::new();
+
+  for ip_line in parsed_info.ip_lines.iter() {
+    for address in &ip_line.ipv6 {
+      // the reverse zone is the zone of the prefix, there is none without it
+      let Some(prefix) = &prefix else {
+        return Err(ValidationError::MissingIpv6Prefix(
+          ip_line.number,
+          address.to_string(),
+        ));
+      };
+      let Some(ip) = ipv6::resolve(address, Some(prefix)) else {
+        return Err(ValidationError::InvalidIpv6Address(
+          ip_line.number,
+          address.to_string(),
+        ));
+      };
+      if !prefix.contains(ip) {
+        return Err(ValidationError::Ipv6AddressOutsidePrefix(
+          ip_line.number,
+          address.to_string(),
+          parsed_info.ipv6_prefix.to_string(),
+        ));
+      }
+      if let Some(Line {
+        number: e_number, ..
+      }) = uniq.insert(ip, ip_line)
+      {
+        return Err(ValidationError::DuplicateIpAddress(
+          ip_line.number,
+          *e_number,
+          ip.to_string(),
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
 fn check_unique_host(parsed_info: &ParsedInfo) -> Result<(), ValidationError> {
   let mut uniq = HashMap::<&str, &Line>::new();
 
@@ -97,11 +218,23 @@ fn check_required_fields(parsed_info: &ParsedInfo) -> Result<(), ValidationError
   if parsed_info.dns_file_name.is_empty() {
     return Err(ValidationError::MissingDNSFileName());
   }
-  if parsed_info.reverse_dns_file_name.is_empty() {
-    return Err(ValidationError::MissingReverseDNSFileName());
+  match (
+    parsed_info.subnets.is_empty(),
+    parsed_info.reverse_dns_file_name.is_empty(),
+  ) {
+    (true, true) => return Err(ValidationError::MissingReverseDNSFileName()),
+    (false, false) => return Err(ValidationError::ReverseDNSFileNameWithSubnets()),
+    _ => {},
   }
   if parsed_info.dhcp_file_name.is_empty() {
     return Err(ValidationError::MissingDHCPFileName());
   }
+  let has_ipv6 = parsed_info
+    .ip_lines
+    .iter()
+    .any(|line| !line.ipv6.is_empty());
+  if has_ipv6 && parsed_info.reverse_ipv6_dns_file_name.is_empty() {
+    return Err(ValidationError::MissingReverseIPv6DNSFileName());
+  }
   Ok(())
 }
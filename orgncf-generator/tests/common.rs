@@ -1,4 +1,4 @@
-/// Various utils used in all test files
+//! Various utils used in all test files
 
 #[macro_export]
 macro_rules! check_err {
@@ -0,0 +1,82 @@
+use ::orgncf_generator::ipv6::{Ipv6Prefix, resolve, reverse_name};
+use ::orgncf_generator::process;
+use indoc::indoc;
+
+#[test]
+fn test_prefix() {
+    let prefix = Ipv6Prefix::parse("fd00:0:0:1::/64").unwrap();
+    assert_eq!("1.0.0.0.0.0.0.0.0.0.0.0.0.0.d.f.ip6.arpa", prefix.zone());
+    assert_eq!(None, Ipv6Prefix::parse("fd00::/62"));
+    assert_eq!(None, Ipv6Prefix::parse("fd00::1/64"));
+    assert_eq!(None, Ipv6Prefix::parse("fd00::"));
+}
+
+#[test]
+fn test_resolve() {
+    let prefix = Ipv6Prefix::parse("fd00:0:0:1::/64").unwrap();
+    assert_eq!(Some("fd00:0:0:1::5".parse().unwrap()), resolve("::5", Some(&prefix)));
+    assert_eq!(Some("fd00:0:0:2::5".parse().unwrap()), resolve("fd00:0:0:2::5", Some(&prefix)));
+    assert_eq!(None, resolve("::5", None));
+    assert_eq!(None, resolve("::1:0:0:0:5", Some(&prefix)));
+    assert_eq!(None, resolve("fd00::zz", Some(&prefix)));
+}
+
+#[test]
+fn test_reverse_name() {
+    let prefix = Ipv6Prefix::parse("fd00:0:0:1::/64").unwrap();
+    assert_eq!("5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0", reverse_name("fd00:0:0:1::5".parse().unwrap(), &prefix));
+    assert_eq!("5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.a", reverse_name("fd00:0:0:1:a000::5".parse().unwrap(), &prefix));
+}
+
+#[test]
+fn test_generate() {
+    let input = indoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 fd00:0:0:1::a router gw
+      10.0.0.2 ::2 @
+      10:00:00:00:00:cc 10.0.0.3 printer
+      domain lan
+      dns_file_name db.lan
+      reverse_dns_file_name db.0.0.10
+      dhcp_file_name reservations.conf
+      ipv6_prefix fd00:0:0:1::/64
+      reverse_ipv6_dns_file_name db.fd00.0.0.1
+      dhcpv6_file_name reservations6.conf
+      DNS_PREFIX """ """
+      DNS_SUFFIX """ """
+      DHCP_PREFIX """ """
+    "#};
+    let dir = std::env::temp_dir().join(format!("orgncf-ipv6-test-{}", std::process::id()));
+    let output_dir = dir.to_str().unwrap();
+    process(input, "test_input.ncf", output_dir).unwrap();
+    // the DNS prefix and suffix are blank
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap().trim().to_string();
+
+    assert_eq!(
+        indoc! {"
+          router   IN A     10.0.0.1
+          router   IN AAAA  fd00:0:0:1::1
+          router   IN AAAA  fd00:0:0:1::a
+          gw       IN CNAME router
+          @        IN A     10.0.0.2
+          @        IN AAAA  fd00:0:0:1::2
+          printer  IN A     10.0.0.3"},
+        read("db.lan")
+    );
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 1.0.0.0.0.0.0.0.0.0.0.0.0.0.d.f.ip6.arpa.
+          1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0 IN PTR router.lan.
+          a.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0 IN PTR router.lan.
+          2.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0 IN PTR lan."},
+        read("db.fd00.0.0.1")
+    );
+    assert_eq!(
+        indoc! {"
+          host router {
+            hardware ethernet 10:00:00:00:00:aa;
+            fixed-address6 fd00:0:0:1::1;
+          }"},
+        read("reservations6.conf")
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
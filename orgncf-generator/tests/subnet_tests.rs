@@ -0,0 +1,62 @@
+use ::orgncf_generator::process;
+use indoc::indoc;
+
+#[test]
+fn test_reverse_zone_per_subnet() {
+    let input = indoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 router
+      10.0.1.5 nas
+      10.0.0.7 @
+      192.168.1.130 guest
+      192.168.1.10 camera
+      subnet 10.0.0.0/23
+      subnet 192.168.1.128/26
+      subnet 192.168.1.0/26
+      domain lan
+      dns_file_name db.lan
+      dhcp_file_name reservations.conf
+      DNS_PREFIX """ """
+      DNS_SUFFIX """ """
+      DHCP_PREFIX """ """
+    "#};
+    let dir = std::env::temp_dir().join(format!("orgncf-subnet-test-{}", std::process::id()));
+    let output_dir = dir.to_str().unwrap();
+    process(input, "test_input.ncf", output_dir).unwrap();
+    // the DNS prefix is blank
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap().trim().to_string();
+
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 0.0.10.in-addr.arpa.
+          1   IN PTR router.lan.
+          7   IN PTR lan."},
+        read("db.0.0.10")
+    );
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 1.0.10.in-addr.arpa.
+          5   IN PTR nas.lan."},
+        read("db.1.0.10")
+    );
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 128-191.1.168.192.in-addr.arpa.
+          130 IN PTR guest.lan."},
+        read("db.128-191.1.168.192")
+    );
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 0-63.1.168.192.in-addr.arpa.
+          10  IN PTR camera.lan."},
+        read("db.0-63.1.168.192")
+    );
+    // the /24 zone is written as well, it hands the addresses over to both classless zones
+    assert_eq!(
+        indoc! {"
+          $ORIGIN 1.168.192.in-addr.arpa.
+          $GENERATE 128-191 $ IN CNAME $.128-191.1.168.192.in-addr.arpa.
+          $GENERATE 0-63 $ IN CNAME $.0-63.1.168.192.in-addr.arpa."},
+        read("db.1.168.192")
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
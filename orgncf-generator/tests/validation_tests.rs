@@ -3,7 +3,7 @@ use indoc::{formatdoc, indoc};
 mod common;
 
 fn proc(input: &str) -> Result<(), Box<dyn std::error::Error + '_>> {
-    process(&input, "test_input.ncf", "output_dir")
+    process(input, "test_input.ncf", "output_dir")
 }
 
 const EPILOGUE: &str = indoc! {r#"
@@ -51,3 +51,166 @@ fn test_duplicate_hostname() {
     "#};
     check_err!(proc(&s), "✗ Host name host1.net on line 2 is duplicate of line 1");
 }
+
+const IPV6_EPILOGUE: &str = indoc! {r#"
+      ipv6_prefix fd00:0:0:1::/64
+      reverse_ipv6_dns_file_name db.fd00.0.0.1
+    "#};
+
+#[test]
+fn test_duplicate_ipv6() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 host1.net
+      10:00:00:00:00:bb 10.0.0.2 fd00:0:0:1::1 host2.net
+
+      {IPV6_EPILOGUE}
+      {EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Ip address fd00:0:0:1::1 on line 2 is duplicate of line 1");
+}
+
+#[test]
+fn test_interface_id_without_prefix() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 host1.net
+      reverse_ipv6_dns_file_name db.fd00.0.0.1
+
+      {EPILOGUE}
+    "#};
+    check_err!(
+        proc(&s),
+        "✗ IPv6 address ::1 on line 1 needs a prefix in the configuration (ipv6_prefix)"
+    );
+
+    // a full address has no reverse zone without it either
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 fd00:0:0:1::1 host1.net
+      reverse_ipv6_dns_file_name db.fd00.0.0.1
+
+      {EPILOGUE}
+    "#};
+    check_err!(
+        proc(&s),
+        "✗ IPv6 address fd00:0:0:1::1 on line 1 needs a prefix in the configuration (ipv6_prefix)"
+    );
+}
+
+#[test]
+fn test_ipv6_outside_prefix() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 2001:db8::11 host1.net
+
+      {IPV6_EPILOGUE}
+      {EPILOGUE}
+    "#};
+    check_err!(
+        proc(&s),
+        "✗ IPv6 address 2001:db8::11 on line 1 is outside the prefix fd00:0:0:1::/64 (ipv6_prefix)"
+    );
+}
+
+#[test]
+fn test_invalid_ipv6() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 fd00::1::2 host1.net
+
+      {IPV6_EPILOGUE}
+      {EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Invalid IPv6 address fd00::1::2 on line 1");
+
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 ::1 host1.net
+      ipv6_prefix fd00::/62
+
+      {EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Invalid IPv6 prefix fd00::/62, the length must be a multiple of 4 (ipv6_prefix)");
+}
+
+#[test]
+fn test_missing_reverse_ipv6_file() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 fd00::1 host1.net
+      ipv6_prefix fd00::/64
+
+      {EPILOGUE}
+    "#};
+    check_err!(
+        proc(&s),
+        "✗ No IPv6 reverse DNS file specified in the configuration (reverse_ipv6_dns_file_name)"
+    );
+}
+
+const SUBNET_EPILOGUE: &str = indoc! {r#"
+      domain foo.net
+      dns_file_name db.foo
+      dhcp_file_name reservations.conf
+      DNS_PREFIX """ """
+      DNS_SUFFIX """ """
+      DHCP_PREFIX """ """
+    "#};
+
+#[test]
+fn test_ip_outside_reverse_zone() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 host1.net
+      10:00:00:00:00:bb 10.0.1.1 host2.net
+
+      {EPILOGUE}
+    "#};
+    check_err!(
+        proc(&s),
+        "✗ Ip address 10.0.1.1 on line 2 is not in the /24 of line 1, declare subnets (subnet)"
+    );
+}
+
+#[test]
+fn test_ip_outside_subnets() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 host1.net
+      10:00:00:00:00:bb 10.0.2.1 host2.net
+      subnet 10.0.0.0/23
+
+      {SUBNET_EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Ip address 10.0.2.1 on line 2 is not in a declared subnet (subnet)");
+}
+
+#[test]
+fn test_invalid_subnets() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 host1.net
+      subnet 10.0.1.0/23
+
+      {SUBNET_EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Invalid subnet 10.0.1.0/23, expected a network like 10.0.0.0/24 (subnet)");
+
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 host1.net
+      subnet 10.0.0.0/23
+      subnet 10.0.1.0/24
+
+      {SUBNET_EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Subnet 10.0.1.0/24 overlaps subnet 10.0.0.0/23");
+
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.1 host1.net
+      subnet 10.0.0.0/24
+
+      {EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ The reverse zones are named after the subnets, remove reverse_dns_file_name");
+}
+
+#[test]
+fn test_invalid_ip() {
+    let s = formatdoc! {r#"
+      10:00:00:00:00:aa 10.0.0.256 host1.net
+
+      {EPILOGUE}
+    "#};
+    check_err!(proc(&s), "✗ Invalid ip address 10.0.0.256 on line 1");
+}
@@ -0,0 +1,100 @@
+use std::fs;
+use std::hash::Hasher;
+use std::io;
+use std::path::PathBuf;
+
+use fnv::FnvHasher;
+
+/// Last good copy of a downloaded list together with the validators for conditional requests
+#[derive(Debug, PartialEq)]
+pub struct CachedList {
+  pub body: String,
+  pub etag: Option<String>,
+  pub last_modified: Option<String>,
+}
+
+/// On disk cache of downloaded lists keyed by url
+pub struct DownloadCache {
+  dir: PathBuf,
+}
+
+impl DownloadCache {
+  pub fn new(dir: PathBuf) -> io::Result<DownloadCache> {
+    fs::create_dir_all(&dir)?;
+    Ok(DownloadCache { dir })
+  }
+
+  fn path(&self, url: &str, extension: &str) -> PathBuf {
+    self.dir.join(format!("{}.{}", cache_key(url), extension))
+  }
+
+  pub fn load(&self, url: &str) -> Option<CachedList> {
+    let body = fs::read_to_string(self.path(url, "body")).ok()?;
+    let mut etag = None;
+    let mut last_modified = None;
+    if let Ok(meta) = fs::read_to_string(self.path(url, "meta")) {
+      for line in meta.lines() {
+        match line.split_once(' ') {
+          Some(("etag", value)) => etag = Some(value.to_string()),
+          Some(("last-modified", value)) => last_modified = Some(value.to_string()),
+          _ => {}
+        }
+      }
+    }
+    Some(CachedList { body, etag, last_modified })
+  }
+
+  /// Writes to temporary files first, so an interrupted run never leaves a truncated copy behind
+  pub fn store(&self, url: &str, body: &str, etag: Option<&str>, last_modified: Option<&str>) -> io::Result<()> {
+    let mut meta = format!("url {}\n", url);
+    if let Some(etag) = etag {
+      meta.push_str(&format!("etag {}\n", etag));
+    }
+    if let Some(last_modified) = last_modified {
+      meta.push_str(&format!("last-modified {}\n", last_modified));
+    }
+    for (extension, content) in [("body", body), ("meta", meta.as_str())] {
+      let path = self.path(url, extension);
+      let tmp = self.path(url, &format!("{}.tmp", extension));
+      fs::write(&tmp, content)?;
+      fs::rename(&tmp, &path)?;
+    }
+    Ok(())
+  }
+}
+
+/// Makes a file name out of the url, readable but still unique thanks to the hash suffix
+pub fn cache_key(url: &str) -> String {
+  let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+  let readable: String =
+    without_scheme.chars().take(80).map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' }).collect();
+  let mut hasher = FnvHasher::default();
+  hasher.write(url.as_bytes());
+  format!("{}-{:016x}", readable, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cache_key_test() {
+    let key = cache_key("https://v.firebog.net/hosts/lists.php?type=nocross");
+    assert!(key.starts_with("v.firebog.net_hosts_lists.php_type_nocross-"));
+    assert_ne!(key, cache_key("http://v.firebog.net/hosts/lists.php?type=nocross"));
+  }
+
+  #[test]
+  fn store_and_load_test() {
+    let dir = std::env::temp_dir().join(format!("shared-cache-test-{}", std::process::id()));
+    let cache = DownloadCache::new(dir.clone()).unwrap();
+    let url = "https://example.com/hosts";
+    assert_eq!(None, cache.load(url));
+
+    cache.store(url, "0.0.0.0 ads.example.com\n", Some("\"v1\""), None).unwrap();
+    let expected =
+      CachedList { body: "0.0.0.0 ads.example.com\n".to_string(), etag: Some("\"v1\"".to_string()), last_modified: None };
+    assert_eq!(Some(expected), cache.load(url));
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,88 @@
+use std::io::{self, Cursor, Read};
+
+use flate2::read::MultiGzDecoder;
+use liblzma::read::XzDecoder;
+use zip::ZipArchive;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZIP_MAGIC: &[u8] = &[b'P', b'K', 0x03, 0x04];
+
+/// Turns a downloaded or local list into text, gzip, xz and zip compressed lists are recognized by their magic bytes
+/// all files of a zip archive are concatenated
+pub fn decode(bytes: &[u8]) -> io::Result<String> {
+  let mut decompressed = Vec::new();
+  let bytes = if bytes.starts_with(GZIP_MAGIC) {
+    MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    &decompressed
+  } else if bytes.starts_with(XZ_MAGIC) {
+    XzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    &decompressed
+  } else if bytes.starts_with(ZIP_MAGIC) {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    for i in 0..archive.len() {
+      let mut file = archive.by_index(i)?;
+      if file.is_file() {
+        file.read_to_end(&mut decompressed)?;
+        if !decompressed.ends_with(b"\n") {
+          decompressed.push(b'\n');
+        }
+      }
+    }
+    &decompressed
+  } else {
+    bytes
+  };
+  Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use flate2::Compression;
+  use flate2::write::GzEncoder;
+  use liblzma::write::XzEncoder;
+  use zip::ZipWriter;
+  use zip::write::SimpleFileOptions;
+
+  use super::decode;
+
+  const LIST: &str = "0.0.0.0 ads.example.com\n";
+
+  #[test]
+  fn plain_test() {
+    assert_eq!(LIST, decode(LIST.as_bytes()).unwrap());
+  }
+
+  #[test]
+  fn gzip_test() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(LIST.as_bytes()).unwrap();
+    assert_eq!(LIST, decode(&encoder.finish().unwrap()).unwrap());
+  }
+
+  #[test]
+  fn xz_test() {
+    let mut encoder = XzEncoder::new(Vec::new(), 6);
+    encoder.write_all(LIST.as_bytes()).unwrap();
+    assert_eq!(LIST, decode(&encoder.finish().unwrap()).unwrap());
+  }
+
+  #[test]
+  fn zip_test() {
+    let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer.start_file("ads.txt", SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"ads.example.com").unwrap();
+    writer.add_directory("more/", SimpleFileOptions::default()).unwrap();
+    writer.start_file("more/trackers.txt", SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"tracker.example.com\n").unwrap();
+    let archive = writer.finish().unwrap().into_inner();
+    assert_eq!("ads.example.com\ntracker.example.com\n", decode(&archive).unwrap());
+  }
+
+  #[test]
+  fn broken_archive_test() {
+    assert!(decode(&[0x1f, 0x8b, 0x00, 0x01]).is_err());
+  }
+}
@@ -0,0 +1,48 @@
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Everything that can go wrong while resolving and downloading the lists
+#[derive(Error, Debug)]
+pub enum FetchError {
+  #[error("✗ Could not set up the http client: {0}")]
+  Client(#[source] reqwest::Error),
+  #[error("✗ Could not use the cache directory 「{dir}」: {source}")]
+  CacheDir { dir: PathBuf, source: io::Error },
+  #[error("✗ Could not read the lists of lists 「{path}」: {source}")]
+  ListsFile { path: PathBuf, source: io::Error },
+  #[error("✗ No urls found in 「{0}」")]
+  EmptyListsFile(PathBuf),
+  #[error("✗ Skipping 「{line}」 in 「{list}」: {reason}")]
+  InvalidSource { list: String, line: String, reason: String },
+  #[error("✗ Include cycle 「{0}」")]
+  IncludeCycle(String),
+  #[error("✗ 「{url}」 is included more than {max} levels deep")]
+  IncludeTooDeep { url: String, max: usize },
+  #[error("✗ Could not read 「{url}」: {source}")]
+  Read { url: String, source: io::Error },
+  #[error("✗ Could not download 「{url}」: {source}")]
+  Download { url: String, source: reqwest_middleware::Error },
+  #[error("✗ Rejected 「{url}」: {reason}")]
+  Rejected { url: String, reason: String },
+  #[error("✗ 「{0}」 has no valid content")]
+  NoValidContent(String),
+}
+
+impl FetchError {
+  /// A lists of lists the user pointed at can't be read at all, as opposed to a single list or include failing
+  /// an empty lists of lists only has nothing to add to the others
+  pub fn is_unreadable_lists_file(&self) -> bool {
+    matches!(self, FetchError::ListsFile { .. })
+  }
+}
+
+/// A zone file that can't be read or doesn't make sense
+#[derive(Error, Debug)]
+pub enum ZoneError {
+  #[error("✗ Could not read the zone file 「{path}」: {source}")]
+  Read { path: PathBuf, source: io::Error },
+  #[error("✗ {file} line {line}: {message}")]
+  Syntax { file: String, line: usize, message: String },
+}
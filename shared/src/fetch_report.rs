@@ -0,0 +1,144 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
+
+use crate::error::FetchError;
+use crate::list_of_lists::FetchResult;
+
+/// Command line settings for reporting on the downloads
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+  /// Show a progress bar while downloading, only on an interactive terminal
+  #[arg(long)]
+  pub progress: bool,
+
+  /// Print a table with the outcome of every download at the end
+  #[arg(long)]
+  pub summary: bool,
+
+  /// Write the outcome of every download as JSON to this file
+  #[arg(long, value_hint = ValueHint::FilePath)]
+  pub summary_json: Option<PathBuf>,
+}
+
+/// Outcome of downloading a single list
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DownloadSummary {
+  pub url: String,
+  /// HTTP status, missing for local files and when the server could not be reached
+  pub status: Option<u16>,
+  /// Size of the list as received, before unpacking
+  pub bytes: usize,
+  pub valid_lines: usize,
+  pub duration_ms: u64,
+  /// The cached copy was used, because the list was not modified or the download failed
+  pub cache_hit: bool,
+  pub error: Option<String>,
+}
+
+/// Everything fetch_lists found out, the usable lists and how every download went
+#[derive(Default)]
+pub struct FetchReport {
+  /// Lists with valid content, ready to be used
+  pub lists: Vec<FetchResult>,
+  /// One entry per download, including the failed ones
+  pub downloads: Vec<DownloadSummary>,
+  /// Everything that went wrong, lists of lists files, includes and downloads
+  pub failures: Vec<FetchError>,
+}
+
+impl FetchReport {
+  pub fn write_table(&self, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{:>6} {:>12} {:>10} {:>8} {:>5}  url", "status", "bytes", "valid", "seconds", "cache")?;
+    for d in &self.downloads {
+      let status = d.status.map_or("-".to_string(), |status| status.to_string());
+      write!(
+        out,
+        "{:>6} {:>12} {:>10} {:>8.2} {:>5}  {}",
+        status,
+        d.bytes.to_formatted_string(&Locale::en_NL),
+        d.valid_lines.to_formatted_string(&Locale::en_NL),
+        d.duration_ms as f64 / 1000.0,
+        if d.cache_hit { "hit" } else { "" },
+        d.url
+      )?;
+      if let Some(error) = &d.error {
+        write!(out, "  ✗ {}", error)?;
+      }
+      writeln!(out)?;
+    }
+    Ok(())
+  }
+
+  pub fn to_json(&self) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&self.downloads)
+  }
+
+  /// Prints the table and writes the JSON file as asked for on the command line
+  pub fn publish(&self, args: &ReportArgs) -> io::Result<()> {
+    if args.summary {
+      self.write_table(&mut io::stderr().lock())?;
+    }
+    if let Some(path) = &args.summary_json {
+      fs::write(path, self.to_json()?)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn report() -> FetchReport {
+    FetchReport {
+      lists: Vec::new(),
+      failures: Vec::new(),
+      downloads: vec![
+        DownloadSummary {
+          url: "https://example.com/hosts".to_string(),
+          status: Some(200),
+          bytes: 123456,
+          valid_lines: 4321,
+          duration_ms: 420,
+          cache_hit: false,
+          error: None,
+        },
+        DownloadSummary {
+          url: "https://example.com/gone".to_string(),
+          status: Some(404),
+          duration_ms: 10,
+          error: Some("HTTP status client error (404 Not Found)".to_string()),
+          ..DownloadSummary::default()
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn table_test() {
+    let mut out = Vec::new();
+    report().write_table(&mut out).unwrap();
+    assert_eq!(
+      indoc::indoc! {"
+        status        bytes      valid  seconds cache  url
+           200      123.456      4.321     0.42        https://example.com/hosts
+           404            0          0     0.01        https://example.com/gone  ✗ HTTP status client error (404 Not Found)
+      "},
+      String::from_utf8(out).unwrap()
+    );
+  }
+
+  #[test]
+  fn json_test() {
+    let json = report().to_json().unwrap();
+    assert!(json.contains("\"url\": \"https://example.com/hosts\""));
+    assert!(json.contains("\"status\": 404"));
+    assert!(json.contains("\"cache_hit\": false"));
+    assert!(json.contains("\"error\": null"));
+  }
+}
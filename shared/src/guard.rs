@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use log::{error, info, warn};
+
+/// Sources smaller than this are too noisy to compare one by one, they still count in the total
+const MIN_SOURCE_LINES: usize = 100;
+
+/// Command line settings for the sanity check against the previous run
+#[derive(Args, Debug, Clone)]
+pub struct GuardArgs {
+  /// File remembering the number of valid lines per source from the last successful run, enables the sanity check
+  #[arg(long, value_hint = ValueHint::FilePath)]
+  pub state_file: Option<PathBuf>,
+
+  /// Refuse to write the output if the total number of valid lines drops by more than this percentage
+  #[arg(long, default_value_t = 30.0)]
+  pub max_shrink: f64,
+
+  /// Refuse to write the output if the total number of valid lines grows by more than this percentage
+  #[arg(long, default_value_t = 100.0)]
+  pub max_growth: f64,
+
+  /// Refuse to write the output if a single source drops by more than this percentage
+  #[arg(long, default_value_t = 50.0)]
+  pub max_source_shrink: f64,
+
+  /// Refuse to write the output if a single source grows by more than this percentage
+  #[arg(long, default_value_t = 400.0)]
+  pub max_source_growth: f64,
+
+  /// Write the output even if the sanity check fails
+  #[arg(long)]
+  pub force: bool,
+}
+
+/// Number of valid lines per source of a run
+#[derive(Debug, Default, PartialEq)]
+pub struct RunState {
+  pub sources: BTreeMap<String, usize>,
+}
+
+impl RunState {
+  pub fn total(&self) -> usize {
+    self.sources.values().sum()
+  }
+
+  /// Reads the state file, a missing file means there was no previous run
+  pub fn load(path: &Path) -> io::Result<Option<RunState>> {
+    let content = match fs::read_to_string(path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+      Err(e) => return Err(e),
+    };
+    let mut sources = BTreeMap::new();
+    for line in content.lines() {
+      if let Some((count, source)) = line.split_once(' ')
+        && let Ok(count) = count.parse()
+      {
+        sources.insert(source.to_string(), count);
+      }
+    }
+    Ok(Some(RunState { sources }))
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    let content: String = self.sources.iter().map(|(source, count)| format!("{} {}\n", count, source)).collect();
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Violation {
+  TotalShrank { previous: usize, current: usize },
+  TotalGrew { previous: usize, current: usize },
+  SourceShrank { source: String, previous: usize, current: usize },
+  SourceGrew { source: String, previous: usize, current: usize },
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Violation::TotalShrank { previous, current } => {
+        write!(f, "✗ Total valid lines dropped from {} to {} ({:.1}%)", previous, current, change(*previous, *current))
+      }
+      Violation::TotalGrew { previous, current } => {
+        write!(f, "✗ Total valid lines grew from {} to {} (+{:.1}%)", previous, current, change(*previous, *current))
+      }
+      Violation::SourceShrank { source, previous, current } => {
+        write!(f, "✗ 「{}」 dropped from {} to {} valid lines ({:.1}%)", source, previous, current, change(*previous, *current))
+      }
+      Violation::SourceGrew { source, previous, current } => {
+        write!(f, "✗ 「{}」 grew from {} to {} valid lines (+{:.1}%)", source, previous, current, change(*previous, *current))
+      }
+    }
+  }
+}
+
+fn change(previous: usize, current: usize) -> f64 {
+  (current as f64 - previous as f64) * 100.0 / previous as f64
+}
+
+/// Compares the current run with the previous successful one
+/// a source that disappeared counts as shrunk to zero
+pub fn check(previous: &RunState, current: &RunState, args: &GuardArgs) -> Vec<Violation> {
+  let mut violations = Vec::new();
+
+  let (previous_total, current_total) = (previous.total(), current.total());
+  if previous_total > 0 {
+    let delta = change(previous_total, current_total);
+    if delta < -args.max_shrink {
+      violations.push(Violation::TotalShrank { previous: previous_total, current: current_total });
+    } else if delta > args.max_growth {
+      violations.push(Violation::TotalGrew { previous: previous_total, current: current_total });
+    }
+  }
+
+  for (source, &previous) in previous.sources.iter().filter(|(_, count)| **count >= MIN_SOURCE_LINES) {
+    let current = current.sources.get(source).copied().unwrap_or(0);
+    let delta = change(previous, current);
+    if delta < -args.max_source_shrink {
+      violations.push(Violation::SourceShrank { source: source.clone(), previous, current });
+    } else if delta > args.max_source_growth {
+      violations.push(Violation::SourceGrew { source: source.clone(), previous, current });
+    }
+  }
+  violations
+}
+
+/// Runs the sanity check if a state file was given, an error means the output should not be written
+pub fn enforce_guard(args: &GuardArgs, current: &RunState) -> Result<(), String> {
+  let Some(state_file) = &args.state_file else {
+    return Ok(());
+  };
+  let previous = match RunState::load(state_file) {
+    Ok(Some(previous)) => previous,
+    Ok(None) => {
+      info!("No previous run in 「{}」, skipping the sanity check", state_file.display());
+      return Ok(());
+    }
+    Err(e) => return Err(format!("Could not read the state file 「{}」: {}", state_file.display(), e)),
+  };
+  let violations = check(&previous, current, args);
+  if violations.is_empty() {
+    info!("✓ Sanity check against the previous run passed");
+    return Ok(());
+  }
+  if args.force {
+    violations.iter().for_each(|v| warn!("{}", v));
+    warn!("Sanity check failed but --force was given, writing the output anyway");
+    Ok(())
+  } else {
+    violations.iter().for_each(|v| error!("{}", v));
+    Err(format!("Refusing to write the output, {} sanity check(s) failed, use --force to override", violations.len()))
+  }
+}
+
+/// Remembers the current run as the last successful one
+pub fn record_run(args: &GuardArgs, current: &RunState) -> io::Result<()> {
+  match &args.state_file {
+    Some(state_file) => current.save(state_file),
+    None => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args() -> GuardArgs {
+    GuardArgs {
+      state_file: None,
+      max_shrink: 30.0,
+      max_growth: 100.0,
+      max_source_shrink: 50.0,
+      max_source_growth: 400.0,
+      force: false,
+    }
+  }
+
+  fn state(sources: &[(&str, usize)]) -> RunState {
+    RunState { sources: sources.iter().map(|(s, c)| (s.to_string(), *c)).collect() }
+  }
+
+  #[test]
+  fn within_thresholds_test() {
+    let previous = state(&[("a", 1000), ("b", 1000), ("tiny", 10)]);
+    let current = state(&[("a", 900), ("b", 1200), ("tiny", 1)]);
+    assert!(check(&previous, &current, &args()).is_empty());
+  }
+
+  #[test]
+  fn violations_test() {
+    let previous = state(&[("a", 1000), ("b", 1000), ("c", 200)]);
+    let current = state(&[("a", 1000), ("c", 1200)]);
+    let violations = check(&previous, &current, &args());
+    assert_eq!(
+      vec![
+        Violation::SourceShrank { source: "b".to_string(), previous: 1000, current: 0 },
+        Violation::SourceGrew { source: "c".to_string(), previous: 200, current: 1200 },
+      ],
+      violations
+    );
+    assert_eq!("✗ 「b」 dropped from 1000 to 0 valid lines (-100.0%)", violations[0].to_string());
+  }
+
+  #[test]
+  fn total_shrank_test() {
+    let previous = state(&[("a", 1000), ("b", 1000)]);
+    let current = state(&[("a", 1000), ("b", 300)]);
+    assert_eq!(
+      vec![
+        Violation::TotalShrank { previous: 2000, current: 1300 },
+        Violation::SourceShrank { source: "b".to_string(), previous: 1000, current: 300 },
+      ],
+      check(&previous, &current, &args())
+    );
+  }
+
+  #[test]
+  fn save_and_load_test() {
+    let path = std::env::temp_dir().join(format!("shared-guard-test-{}.state", std::process::id()));
+    assert_eq!(None, RunState::load(&path).unwrap());
+    let current = state(&[("https://example.com/hosts", 1000), ("/etc/dns-block/block_files.d/own list.txt", 3)]);
+    current.save(&path).unwrap();
+    assert_eq!(Some(current), RunState::load(&path).unwrap());
+    fs::remove_file(path).unwrap();
+  }
+}
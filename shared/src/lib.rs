@@ -1,7 +1,25 @@
+mod cache;
+mod decompress;
+mod error;
+mod fetch_report;
+mod guard;
+mod list_format;
 mod list_of_lists;
 mod logging;
 mod man;
+mod source;
+mod throttle;
+mod verify;
+mod zone;
 
-pub use list_of_lists::fetch_lists;
+pub use cache::cache_key;
+pub use error::{FetchError, ZoneError};
+pub use fetch_report::{DownloadSummary, FetchReport, ReportArgs};
+pub use guard::{GuardArgs, RunState, enforce_guard, record_run};
+pub use list_format::{ListFormat, is_valid_domain};
+pub use list_of_lists::{FetchOptions, FetchResult, fetch_lists};
 pub use logging::setup_logging;
 pub use man::{ManExample, generate_man_page};
+pub use source::{Action, Source, SourceOptions};
+pub use verify::hex_sha256;
+pub use zone::{ZoneRecord, parse_zone, read_zone, reverse_name_to_ip};
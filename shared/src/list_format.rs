@@ -0,0 +1,283 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// How many content lines are looked at to guess the format of a list
+const DETECTION_LINES: usize = 500;
+
+/// ABP/AdGuard options that still make sense when blocking a whole domain
+const DOMAIN_LEVEL_OPTIONS: [&str; 4] = ["important", "all", "document", "doc"];
+
+/// dnsmasq answers that block the domain, an address is a local override and a server a forwarding rule
+const DNSMASQ_BLOCKING_ANSWERS: [&str; 5] = ["", "0.0.0.0", "::", "127.0.0.1", "#"];
+
+/// Syntax of a block list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+  /// `ads.example.com`, `*.example.com`, also the fallback for anything unknown
+  #[default]
+  Plain,
+  /// `0.0.0.0 ads.example.com tracker.example.com`
+  Hosts,
+  /// `||ads.example.com^`, only the rules blocking a whole domain are used
+  Adblock,
+  /// `address=/ads.example.com/0.0.0.0`
+  Dnsmasq,
+  /// `ads.example.com CNAME .`
+  Rpz,
+}
+
+impl ListFormat {
+  pub const ALL: [ListFormat; 5] =
+    [ListFormat::Plain, ListFormat::Hosts, ListFormat::Adblock, ListFormat::Dnsmasq, ListFormat::Rpz];
+
+  /// Guesses the format from the first lines, every line votes for the syntax it looks like
+  pub fn detect(content: &str) -> ListFormat {
+    let mut votes = [0usize; 5];
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty()).take(DETECTION_LINES) {
+      if let Some(format) = ListFormat::look_alike(line) {
+        votes[format as usize] += 1;
+      }
+    }
+    // on a tie the order of ALL decides, plain comes before the more exotic formats
+    ListFormat::ALL.into_iter().rev().max_by_key(|format| votes[*format as usize]).unwrap_or_default()
+  }
+
+  fn look_alike(line: &str) -> Option<ListFormat> {
+    if line.starts_with("||") || line.starts_with("@@") || line.starts_with('!') || line.starts_with('[') {
+      return Some(ListFormat::Adblock);
+    }
+    if line.starts_with("address=/") || line.starts_with("server=/") || line.starts_with("local=/") {
+      return Some(ListFormat::Dnsmasq);
+    }
+    if line.starts_with(';') || line.starts_with('$') || line.to_ascii_lowercase().contains(" cname ") {
+      return Some(ListFormat::Rpz);
+    }
+    if line.starts_with('#') {
+      return None;
+    }
+    let mut tokens = line.split_whitespace();
+    match (tokens.next(), tokens.next()) {
+      (Some(first), Some(_)) if first.parse::<IpAddr>().is_ok() => Some(ListFormat::Hosts),
+      (Some(_), None) => Some(ListFormat::Plain),
+      _ => None,
+    }
+  }
+
+  /// Calls found with every domain a line of the list blocks, the names are not validated yet
+  pub fn domains<'a>(self, line: &'a str, mut found: impl FnMut(&'a str)) {
+    match self {
+      ListFormat::Plain => {
+        if let Some(name) = strip_comment(line, '#').split_whitespace().next_back().and_then(clean_name) {
+          found(name);
+        }
+      }
+      ListFormat::Hosts => {
+        let mut tokens = strip_comment(line, '#').split_whitespace();
+        match tokens.next() {
+          Some(first) if first.parse::<IpAddr>().is_ok() => tokens.filter_map(clean_name).for_each(found),
+          // not a hosts line after all, treat it as a plain domain
+          Some(_) => ListFormat::Plain.domains(line, found),
+          None => {}
+        }
+      }
+      ListFormat::Adblock => {
+        if let Some(name) = adblock_domain(line.trim()) {
+          found(name);
+        }
+      }
+      ListFormat::Dnsmasq => {
+        // only whole lines are comments, # is also an answer
+        let line = line.trim();
+        let Some(rest) = ["address=/", "server=/", "local=/"].iter().find_map(|prefix| line.strip_prefix(prefix)) else {
+          return;
+        };
+        // address=/a.com/b.com/0.0.0.0, the part after the last slash is the answer
+        if let Some((names, answer)) = rest.rsplit_once('/')
+          && DNSMASQ_BLOCKING_ANSWERS.contains(&answer.trim())
+        {
+          names.split('/').filter_map(clean_name).for_each(found);
+        }
+      }
+      ListFormat::Rpz => {
+        let line = strip_comment(line, ';');
+        if line.starts_with(char::is_whitespace) || line.starts_with('$') || line.starts_with('@') {
+          return;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        // owner [ttl] [class] CNAME target
+        if let Some(cname) = tokens.iter().position(|token| token.eq_ignore_ascii_case("cname"))
+          && let Some(&target) = tokens.get(cname + 1)
+          && (target == "." || target == "*.")
+          && let Some(name) = clean_name(tokens[0])
+        {
+          found(name);
+        }
+      }
+    }
+  }
+
+  /// Number of lines naming at least one valid domain, zero would indicate an invalid list
+  pub fn count_valid_lines(self, content: &str) -> usize {
+    content
+      .lines()
+      .filter(|line| {
+        let mut valid = false;
+        self.domains(line, |name| valid |= is_valid_domain(name));
+        valid
+      })
+      .count()
+  }
+}
+
+impl fmt::Display for ListFormat {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      ListFormat::Plain => "plain",
+      ListFormat::Hosts => "hosts",
+      ListFormat::Adblock => "adblock",
+      ListFormat::Dnsmasq => "dnsmasq",
+      ListFormat::Rpz => "rpz",
+    };
+    f.write_str(name)
+  }
+}
+
+impl FromStr for ListFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<ListFormat, String> {
+    match s.to_ascii_lowercase().as_str() {
+      "hosts" => Ok(ListFormat::Hosts),
+      "plain" | "domains" => Ok(ListFormat::Plain),
+      "adblock" | "abp" | "adguard" => Ok(ListFormat::Adblock),
+      "dnsmasq" => Ok(ListFormat::Dnsmasq),
+      "rpz" => Ok(ListFormat::Rpz),
+      _ => Err(format!("unknown list format 「{}」, expected hosts, plain, adblock, dnsmasq or rpz", s)),
+    }
+  }
+}
+
+/// A domain with at least one dot that the public suffix list accepts
+pub fn is_valid_domain(name: &str) -> bool {
+  name.contains('.') && addr::parse_dns_name(name).is_ok()
+}
+
+fn strip_comment(line: &str, marker: char) -> &str {
+  match line.find(marker) {
+    Some(idx) => &line[0..idx],
+    None => line,
+  }
+}
+
+/// A leading `*.` blocks the domain and everything below it, which is what blocking the domain does anyway
+/// wildcards anywhere else can't be expressed with dns blocking
+fn clean_name(token: &str) -> Option<&str> {
+  let name = token.strip_prefix("*.").unwrap_or(token).trim_end_matches('.');
+  let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '*');
+  if name.is_empty() || name.contains('*') { None } else { Some(name) }
+}
+
+/// `||example.com^` with an optional `$option,...` suffix, anything more specific than a domain is skipped
+fn adblock_domain(rule: &str) -> Option<&str> {
+  let rest = rule.strip_prefix("||")?;
+  let (pattern, options) = match rest.split_once('$') {
+    Some((pattern, options)) => (pattern, Some(options)),
+    None => (rest, None),
+  };
+  if let Some(options) = options
+    && !options.split(',').all(|option| DOMAIN_LEVEL_OPTIONS.contains(&option.trim()))
+  {
+    return None;
+  }
+  let name = pattern.strip_suffix('^').or_else(|| pattern.strip_suffix("^|")).unwrap_or(pattern);
+  if name.is_empty() || name.contains(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')) {
+    return None;
+  }
+  Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ListFormat::{self, *};
+
+  fn domains(format: ListFormat, line: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    format.domains(line, |name| found.push(name));
+    found
+  }
+
+  #[test]
+  fn hosts_test() {
+    assert_eq!(vec!["ads.example.com", "tracker.example.com"], domains(Hosts, "0.0.0.0 ads.example.com tracker.example.com # x"));
+    assert_eq!(vec!["ads.example.com"], domains(Hosts, "::1\tads.example.com"));
+    assert_eq!(vec!["ads.example.com"], domains(Hosts, "ads.example.com"));
+    assert!(domains(Hosts, "# 0.0.0.0 ads.example.com").is_empty());
+  }
+
+  #[test]
+  fn plain_test() {
+    assert_eq!(vec!["example.com"], domains(Plain, "*.example.com"));
+    assert_eq!(vec!["example.com"], domains(Plain, "example.com. # trailing dot"));
+    assert!(domains(Plain, "ads*.example.com").is_empty());
+  }
+
+  #[test]
+  fn adblock_test() {
+    assert_eq!(vec!["ads.example.com"], domains(Adblock, "||ads.example.com^"));
+    assert_eq!(vec!["ads.example.com"], domains(Adblock, "||ads.example.com^$important"));
+    assert!(domains(Adblock, "||ads.example.com^$third-party").is_empty());
+    assert!(domains(Adblock, "||example.com/banner.gif").is_empty());
+    assert!(domains(Adblock, "@@||example.com^").is_empty());
+    assert!(domains(Adblock, "! Title: some list").is_empty());
+    assert!(domains(Adblock, "example.com##.ad-banner").is_empty());
+  }
+
+  #[test]
+  fn dnsmasq_test() {
+    assert_eq!(vec!["ads.example.com"], domains(Dnsmasq, "address=/ads.example.com/0.0.0.0"));
+    assert_eq!(vec!["a.example.com", "b.example.com"], domains(Dnsmasq, "address=/a.example.com/b.example.com/"));
+    assert_eq!(vec!["ads.example.com"], domains(Dnsmasq, "server=/ads.example.com/"));
+    assert_eq!(vec!["ads.example.com"], domains(Dnsmasq, "address=/ads.example.com/#"));
+    assert_eq!(vec!["ads.example.com"], domains(Dnsmasq, "address=/ads.example.com/::"));
+    assert!(domains(Dnsmasq, "# address=/ads.example.com/").is_empty());
+  }
+
+  #[test]
+  fn dnsmasq_not_blocking_test() {
+    // forwarding to another server
+    assert!(domains(Dnsmasq, "server=/corp.example/10.0.0.1").is_empty());
+    assert!(domains(Dnsmasq, "server=/corp.example/10.0.0.1#5353").is_empty());
+    // a local override
+    assert!(domains(Dnsmasq, "address=/nas.example/192.168.1.5").is_empty());
+    assert!(domains(Dnsmasq, "address=/nas.example/fd00::5").is_empty());
+  }
+
+  #[test]
+  fn rpz_test() {
+    assert_eq!(vec!["ads.example.com"], domains(Rpz, "ads.example.com CNAME ."));
+    assert_eq!(vec!["example.com"], domains(Rpz, "*.example.com 3600 IN CNAME . ; comment"));
+    assert!(domains(Rpz, "ok.example.com CNAME rpz-passthru.").is_empty());
+    assert!(domains(Rpz, "@ IN SOA localhost. root.localhost. (").is_empty());
+    assert!(domains(Rpz, "$TTL 60").is_empty());
+  }
+
+  #[test]
+  fn detect_test() {
+    assert_eq!(Hosts, ListFormat::detect("# comment\n127.0.0.1 localhost\n0.0.0.0 ads.example.com\n"));
+    assert_eq!(Plain, ListFormat::detect("# comment\nads.example.com\n"));
+    assert_eq!(Adblock, ListFormat::detect("[Adblock Plus 2.0]\n! Title\n||ads.example.com^\n"));
+    assert_eq!(Dnsmasq, ListFormat::detect("address=/ads.example.com/0.0.0.0\n"));
+    assert_eq!(Rpz, ListFormat::detect("$TTL 60\n; comment\nads.example.com CNAME .\n"));
+    assert_eq!(Plain, ListFormat::detect(""));
+  }
+
+  #[test]
+  fn count_valid_lines_test() {
+    let content = "[Adblock Plus 2.0]\n! Title\n||ads.example.com^\n||example.com/path\n||tracker.example.net^$important\n";
+    assert_eq!(2, Adblock.count_valid_lines(content));
+    assert_eq!(1, Hosts.count_valid_lines("127.0.0.1 localhost\n0.0.0.0 ads.example.com\n"));
+    assert_eq!("rpz", Rpz.to_string());
+    assert_eq!(Ok(Adblock), "ABP".parse());
+  }
+}
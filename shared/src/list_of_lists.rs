@@ -1,85 +1,242 @@
 use std::collections::HashSet;
-use std::fs::read_to_string;
-use std::path::PathBuf;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use futures::future::join_all;
+use futures::StreamExt;
+use futures::future::{LocalBoxFuture, join_all};
+use futures::stream;
+use indicatif::{ProgressBar, ProgressStyle};
 use log::{error, info, warn};
 use num_format::{Locale, ToFormattedString};
-use reqwest::Client;
+use reqwest::header::{ETAG, HeaderMap, HeaderName, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode, Url};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::RetryTransientMiddleware;
 use reqwest_retry::policies::ExponentialBackoff;
 
-type BoxError = Box<dyn std::error::Error>;
+use crate::cache::{CachedList, DownloadCache};
+use crate::decompress;
+use crate::error::FetchError;
+use crate::fetch_report::{DownloadSummary, FetchReport};
+use crate::list_format::ListFormat;
+use crate::source::{Source, SourceOptions};
+use crate::throttle::{HostLimiter, RetryAfterAwareStrategy, is_throttled, retry_after};
+use crate::verify;
+
+/// Lists of lists can include each other, nesting deeper than this is most likely a mistake
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+const INCLUDE_PREFIX: &str = "#!include ";
+
+/// Never wait longer than this for a throttled server, better use the cached copy or skip the list
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
 
 pub struct FetchResult {
   pub url: String,
   pub text: reqwest::Result<String>,
+  /// Lines holding a valid host name, filled in once the download is validated
+  pub valid_lines: usize,
+  /// Syntax of the list, as declared in the options or detected once the download is validated
+  pub format: ListFormat,
+  /// Options given with the url in the lists of lists file
+  pub options: SourceOptions,
+  /// HTTP status of the response, None for local files and unreachable servers
+  pub status: Option<u16>,
+  /// Size of the list as received, before unpacking
+  pub bytes: usize,
+  /// The text is the cached copy
+  pub cache_hit: bool,
 }
 
-/// Resolves a list of list urls by downloading them all
-pub async fn fetch_lists(lists_file: Option<Vec<PathBuf>>, max_retries: u32) -> Result<Vec<FetchResult>, BoxError> {
-  let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
-  let client = ClientBuilder::new(Client::new()).with(RetryTransientMiddleware::new_with_policy(retry_policy)).build();
+impl FetchResult {
+  fn new(url: String, text: reqwest::Result<String>) -> FetchResult {
+    FetchResult {
+      url,
+      text,
+      valid_lines: 0,
+      format: ListFormat::default(),
+      options: SourceOptions::default(),
+      status: None,
+      bytes: 0,
+      cache_hit: false,
+    }
+  }
+
+  fn from_cache(url: String, cached: CachedList, status: Option<u16>) -> FetchResult {
+    FetchResult { status, cache_hit: true, ..FetchResult::new(url, Ok(cached.body)) }
+  }
+}
 
+/// Settings for downloading the lists
+#[derive(Debug)]
+pub struct FetchOptions {
+  /// How many times to retry if downloading a list fails
+  pub max_retries: u32,
+  /// Directory keeping the last good copy of every list, no caching if missing
+  pub cache_dir: Option<PathBuf>,
+  /// How many lists are downloaded at the same time
+  pub concurrency: usize,
+  /// How many lists are downloaded at the same time from a single host
+  pub per_host: usize,
+  /// Time allowed for a single request, including reading the body
+  pub timeout: Duration,
+  /// Show a progress bar on an interactive terminal
+  pub progress: bool,
+}
+
+impl Default for FetchOptions {
+  fn default() -> FetchOptions {
+    FetchOptions {
+      max_retries: 3,
+      cache_dir: None,
+      concurrency: 8,
+      per_host: 2,
+      timeout: Duration::from_secs(60),
+      progress: false,
+    }
+  }
+}
+
+/// Everything needed to download a list, shared by all downloads of a run
+struct Downloader {
+  client: ClientWithMiddleware,
+  cache: Option<DownloadCache>,
+  hosts: HostLimiter,
+  max_retries: u32,
+}
+
+impl Downloader {
+  fn new(options: &FetchOptions) -> Result<Downloader, FetchError> {
+    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(options.max_retries);
+    let retry = RetryTransientMiddleware::new_with_policy_and_strategy(retry_policy, RetryAfterAwareStrategy);
+    let client =
+      ClientBuilder::new(Client::builder().timeout(options.timeout).build().map_err(FetchError::Client)?).with(retry).build();
+    let cache = match &options.cache_dir {
+      Some(dir) => Some(DownloadCache::new(dir.clone()).map_err(|source| FetchError::CacheDir { dir: dir.clone(), source })?),
+      None => None,
+    };
+    Ok(Downloader { client, cache, hosts: HostLimiter::new(options.per_host), max_retries: options.max_retries })
+  }
+}
+
+/// Resolves a list of list urls by downloading them all
+/// the report holds the lists with valid content, the outcome of every download and everything that failed,
+/// only failing to set up the downloads is an error, what to do about a failed list is up to the caller
+pub async fn fetch_lists(lists_file: Option<Vec<PathBuf>>, options: &FetchOptions) -> Result<FetchReport, FetchError> {
   let lists_files = match lists_file {
     Some(l) => {
       if l.is_empty() {
-        return Ok(Vec::new());
+        return Ok(FetchReport::default());
       } else {
         l
       }
     }
-    None => return Ok(Vec::new()),
+    None => return Ok(FetchReport::default()),
   };
 
-  let file_results = join_all(lists_files.iter().map(|filename| get_url_list(filename, client.clone()))).await;
+  let downloader = Downloader::new(options)?;
+  let file_results = join_all(lists_files.iter().map(|filename| get_url_list(filename, &downloader))).await;
   let mut urls = HashSet::new();
+  let mut sources = Vec::new();
+  let mut failures = Vec::new();
 
-  for result in file_results {
-    // 1. Handle the Result (stop if there's an error)
-    let inner_set = result?;
-
-    for url in inner_set {
+  for (file_sources, mut file_failures) in file_results {
+    failures.append(&mut file_failures);
+    for source in file_sources {
       // 2. Try to insert. If it returns false, it was a duplicate.
-      if !urls.insert(url.clone()) {
-        warn!("「{}」is a duplicate, skipping it", url);
+      if !urls.insert(source.url.clone()) {
+        warn!("「{}」is a duplicate, skipping it", source.url);
+      } else if !source.options.enabled {
+        info!("「{}」 is disabled, skipping it", source.url);
+      } else {
+        sources.push(source);
       }
     }
   }
 
-  // 1. Create a list of futures (one for each request)
-  let tasks = urls.into_iter().map(|url| download_url(url, client.clone()));
+  // drawn on stderr, hidden when that is not a terminal
+  let progress = if options.progress { ProgressBar::new(sources.len() as u64) } else { ProgressBar::hidden() };
+  progress.set_style(
+    ProgressStyle::with_template("{bar:40} {pos}/{len} lists, {elapsed} {msg}").unwrap_or_else(|_| ProgressStyle::default_bar()),
+  );
+
+  // 1. Create a stream of futures (one for each request)
+  let downloader = &downloader;
+  let progress_ref = &progress;
+  let tasks = stream::iter(sources).map(|source| async move {
+    let start = Instant::now();
+    let url = source.url.clone();
+    let result = download_url(source.url, &source.options, downloader).await;
+    progress_ref.inc(1);
+    progress_ref.set_message(url.clone());
+    let result = result.map(|result| FetchResult { options: source.options, ..result });
+    (url, result, start.elapsed())
+  });
 
-  // 2. Execute all tasks in parallel
+  // 2. Execute the tasks in parallel, but only so many at the same time
   info!("Fetching URLs...");
-  let results: Vec<Result<FetchResult, BoxError>> = join_all(tasks).await;
+  let results: Vec<(String, Result<FetchResult, FetchError>, Duration)> =
+    tasks.buffer_unordered(options.concurrency.max(1)).collect().await;
+  progress.finish_and_clear();
 
-  let mut ret = Vec::with_capacity(results.len());
+  let mut report =
+    FetchReport { lists: Vec::with_capacity(results.len()), downloads: Vec::with_capacity(results.len()), failures };
   // 4. Filter for successful results and concatenate
   info!("Validate downloads");
   let mut total_hosts = 0;
-  for fetch_result in results.into_iter().flatten() {
-    match fetch_result.text {
-      Ok(ref content) => {
-        let number_of_valid_lines = number_of_valid_lines(content);
+  for (url, result, duration) in results {
+    let mut summary = DownloadSummary { url, duration_ms: duration.as_millis() as u64, ..DownloadSummary::default() };
+    let mut fetch_result = match result {
+      Ok(fetch_result) => fetch_result,
+      Err(e) => {
+        summary.error = Some(e.to_string());
+        report.downloads.push(summary);
+        report.failures.push(e);
+        continue;
+      }
+    };
+    summary.status = fetch_result.status;
+    summary.bytes = fetch_result.bytes;
+    summary.cache_hit = fetch_result.cache_hit;
+    match std::mem::replace(&mut fetch_result.text, Ok(String::new())) {
+      Ok(content) => {
+        let format = fetch_result.options.format.unwrap_or_else(|| ListFormat::detect(&content));
+        let number_of_valid_lines = format.count_valid_lines(&content);
         if number_of_valid_lines > 0 {
           total_hosts += number_of_valid_lines;
-          info!("{:>12} valid hosts in 「{}」", number_of_valid_lines.to_formatted_string(&Locale::en_NL), fetch_result.url);
-          ret.push(fetch_result);
+          fetch_result.valid_lines = number_of_valid_lines;
+          fetch_result.format = format;
+          summary.valid_lines = number_of_valid_lines;
+          info!(
+            "{:>12} valid hosts in 「{}」 ({})",
+            number_of_valid_lines.to_formatted_string(&Locale::en_NL),
+            fetch_result.url,
+            format
+          );
+          report.lists.push(FetchResult { text: Ok(content), ..fetch_result });
         } else {
           warn!("Url 「{}」 returned no valid content", fetch_result.url);
+          summary.error = Some("no valid content".to_string());
+          report.failures.push(FetchError::NoValidContent(fetch_result.url));
         }
       }
-      Err(e) => warn!("Error fetching a url 「{}」: {}", fetch_result.url, e),
+      Err(e) => {
+        warn!("Error fetching a url 「{}」: {}", fetch_result.url, e);
+        summary.error = Some(e.to_string());
+        report.failures.push(FetchError::Download { url: fetch_result.url, source: e.into() });
+      }
     }
+    report.downloads.push(summary);
   }
+  report.downloads.sort_by(|a, b| a.url.cmp(&b.url));
 
   info!(
     "Finished downloading. We got {} hosts in total but they most likely contain duplicates.",
     total_hosts.to_formatted_string(&Locale::en_NL)
   );
-  Ok(ret)
+  Ok(report)
 }
 
 fn extract_dns_block_url(content: &str) -> Option<&str> {
@@ -88,72 +245,520 @@ fn extract_dns_block_url(content: &str) -> Option<&str> {
   if content.starts_with(prefix) { first_line[prefix.len()..].split_whitespace().next() } else { None }
 }
 
-#[cfg(test)]
-mod test1 {
-  #[test]
-  fn extract_dns_block_url() {
-    let content = "#!dns-block https://v.firebog.net/hosts/lists.php?type=nocross";
-    assert_eq!(Some("https://v.firebog.net/hosts/lists.php?type=nocross"), super::extract_dns_block_url(content));
-  }
+fn header_value(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+  headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
 }
 
-async fn download_url(url: String, client: ClientWithMiddleware) -> Result<FetchResult, BoxError> {
-  let res = match client.get(&url).send().await {
+/// Downloads a list, when a cache is used the request is conditional
+/// and the cached copy stands in if the source is unreachable or broken
+/// a throttled server is retried after the delay it asked for
+/// a list failing its pins is rejected, the cached copy was verified before so it stands in
+async fn download_url(url: String, options: &SourceOptions, downloader: &Downloader) -> Result<FetchResult, FetchError> {
+  if let Some(path) = url.strip_prefix("file://") {
+    let bytes = match fs::read(path) {
+      Ok(bytes) => bytes,
+      Err(e) => {
+        error!("✗ Could not read 「{}」, error: 「{}」", &url, e);
+        return Err(FetchError::Read { url, source: e });
+      }
+    };
+    let text = match verify_and_decode(&url, &bytes, options, downloader).await {
+      Ok(text) => text,
+      Err(reason) => {
+        error!("✗ Rejected 「{}」: {}", &url, reason);
+        return Err(FetchError::Rejected { url, reason });
+      }
+    };
+    info!("✓ read「{url}」");
+    return Ok(FetchResult { bytes: bytes.len(), ..FetchResult::new(url, Ok(text)) });
+  }
+  let cache = downloader.cache.as_ref();
+  let cached = cache.and_then(|cache| cache.load(&url));
+  // pinned lists are always downloaded in full, so the pins are checked on every run
+  let conditional = if options.is_pinned() { None } else { cached.as_ref() };
+  // held until the body is read, so throttled hosts don't get hammered by the other downloads
+  let _permit = downloader.hosts.acquire(&url).await;
+
+  let mut attempts = 0;
+  let response = loop {
+    let mut request = downloader.client.get(&url);
+    if let Some(cached) = conditional {
+      if let Some(etag) = &cached.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+      }
+      if let Some(last_modified) = &cached.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+      }
+    }
+    let response = request.send().await;
+    if let Ok(res) = &response
+      && is_throttled(res.status(), res.headers())
+      && attempts < downloader.max_retries
+      && let Some(delay) = retry_after(res.headers()).filter(|delay| *delay <= MAX_RETRY_AFTER)
+    {
+      attempts += 1;
+      warn!("「{}」 is throttled, retrying in {} seconds", &url, delay.as_secs());
+      tokio::time::sleep(delay).await;
+      continue;
+    }
+    break response;
+  };
+
+  let res = match response {
     Ok(response) => response,
     Err(e) => {
+      if let Some(cached) = cached {
+        warn!("✗ Could not download 「{}」, error: 「{}」, using the cached copy", &url, e);
+        return Ok(FetchResult::from_cache(url, cached, None));
+      }
       error!("✗ Could not download 「{}」, error: 「{}」", &url, e);
-      return Err(Box::new(e) as BoxError);
+      return Err(FetchError::Download { url, source: e });
     }
   };
-  let text = res.text().await;
+
+  if res.status() == StatusCode::NOT_MODIFIED
+    && let Some(cached) = cached
+  {
+    info!("✓ not modified, using the cached copy of「{url}」");
+    return Ok(FetchResult::from_cache(url, cached, Some(StatusCode::NOT_MODIFIED.as_u16())));
+  }
+
+  let status = Some(res.status().as_u16());
+
+  let etag = header_value(res.headers(), ETAG);
+  let last_modified = header_value(res.headers(), LAST_MODIFIED);
+  let body = match res.error_for_status() {
+    Ok(res) => res.bytes().await,
+    Err(e) => Err(e),
+  };
+  let bytes = body.as_ref().map_or(0, |body| body.len());
+  // a list failing verification or a broken archive is treated like a failed download
+  let text = match body {
+    Ok(bytes) => match verify_and_decode(&url, &bytes, options, downloader).await {
+      Ok(text) => Ok(text),
+      Err(e) => {
+        if let Some(cached) = cached {
+          error!("✗ Rejected 「{}」: {}, using the cached copy", &url, e);
+          return Ok(FetchResult::from_cache(url, cached, status));
+        }
+        error!("✗ Rejected 「{}」: {}", &url, e);
+        return Err(FetchError::Rejected { url, reason: e });
+      }
+    },
+    Err(e) => Err(e),
+  };
+  // an error page or an emptied list must not replace the last good copy
+  let valid =
+    text.as_ref().is_ok_and(|body| options.format.unwrap_or_else(|| ListFormat::detect(body)).count_valid_lines(body) > 0);
+  match (&text, cache, cached) {
+    (Ok(body), Some(cache), _) if valid => {
+      if let Err(e) = cache.store(&url, body, etag.as_deref(), last_modified.as_deref()) {
+        warn!("Could not cache 「{}」, error: 「{}」", &url, e);
+      }
+    }
+    (Ok(_), _, Some(cached)) if !valid => {
+      warn!("✗ 「{}」 returned no valid content, using the cached copy", &url);
+      return Ok(FetchResult::from_cache(url, cached, status));
+    }
+    (Err(e), _, Some(cached)) => {
+      warn!("✗ Could not download 「{}」, error: 「{}」, using the cached copy", &url, e);
+      return Ok(FetchResult::from_cache(url, cached, status));
+    }
+    _ => {}
+  }
   info!("✓ downloaded「{url}」");
-  Ok(FetchResult { url, text })
+  Ok(FetchResult { status, bytes, ..FetchResult::new(url, text) })
 }
 
-async fn get_url_list(filename: &PathBuf, client: ClientWithMiddleware) -> Result<HashSet<String>, BoxError> {
-  let mut content = read_to_string(filename)?;
+/// Checks the pins of the source on the list as it was published, then unpacks it
+async fn verify_and_decode(url: &str, bytes: &[u8], options: &SourceOptions, downloader: &Downloader) -> Result<String, String> {
+  if let Some(pin) = &options.sha256 {
+    verify::check_sha256(bytes, pin)?;
+  }
+  if let Some(public_key) = &options.minisign {
+    let signature = fetch_signature(url, options, ".minisig", downloader).await?;
+    verify::check_minisign(bytes, &signature, public_key)?;
+  }
+  if let Some(keyring) = &options.gpg {
+    let signature = fetch_signature(url, options, ".sig", downloader).await?;
+    verify::check_gpg(bytes, &signature, keyring)?;
+  }
+  decompress::decode(bytes).map_err(|e| format!("could not unpack the list: {}", e))
+}
 
-  // if the file has a link url at the start, we try to download a fresh instance
-  if let Some(url) = extract_dns_block_url(&content) {
-    info!("File 「{}」 has a refresh url in the first line, try to download that", filename.display());
-    if let Ok(r) = download_url(url.to_string(), client).await
-      && let Ok(res) = r.text
-    {
-      content = res;
+/// Gets the detached signature, next to the list unless the source says otherwise
+async fn fetch_signature(
+  url: &str,
+  options: &SourceOptions,
+  extension: &str,
+  downloader: &Downloader,
+) -> Result<Vec<u8>, String> {
+  let signature_url = options.signature.clone().unwrap_or_else(|| format!("{}{}", url, extension));
+  let failed = |e: &dyn std::fmt::Display| format!("could not get the signature 「{}」: {}", signature_url, e);
+  if let Some(path) = signature_url.strip_prefix("file://") {
+    return fs::read(path).map_err(|e| failed(&e));
+  }
+  let response = downloader.client.get(&signature_url).send().await.map_err(|e| failed(&e))?;
+  let body = response.error_for_status().map_err(|e| failed(&e))?.bytes().await.map_err(|e| failed(&e))?;
+  Ok(body.to_vec())
+}
+
+/// Reads a lists of lists file given on the command line
+/// returns the sources found and everything that went wrong, an unreadable or empty file makes the sources empty
+async fn get_url_list(filename: &Path, downloader: &Downloader) -> (Vec<Source>, Vec<FetchError>) {
+  let path = match fs::canonicalize(filename) {
+    Ok(path) => path,
+    Err(source) => return (Vec::new(), vec![FetchError::ListsFile { path: filename.to_path_buf(), source }]),
+  };
+  let mut walk = Walk::default();
+  match read_list_of_lists(format!("file://{}", path.display()), downloader, &mut walk).await {
+    Ok(sources) if sources.is_empty() => {
+      let e = FetchError::EmptyListsFile(filename.to_path_buf());
+      warn!("{}", e);
+      walk.failures.push(e);
     }
+    Ok(sources) => return (sources, walk.failures),
+    Err(FetchError::Read { source, .. }) => walk.failures.push(FetchError::ListsFile { path: filename.to_path_buf(), source }),
+    Err(e) => walk.failures.push(e),
   }
-  let mut urls = HashSet::<String>::new();
-  content.lines().for_each(|line| {
-    let cleaned = match line.split('#').next() {
-      Some(it) => it,
-      None => return,
+  (Vec::new(), walk.failures)
+}
+
+/// State of reading a lists of lists file and everything it includes
+#[derive(Default)]
+struct Walk {
+  /// The lists being read right now, finding a url in there again means the includes go round in circles
+  chain: Vec<String>,
+  /// Every lists of lists read so far, each is read only once
+  included: HashSet<String>,
+  /// Includes and lines that were skipped
+  failures: Vec<FetchError>,
+}
+
+/// Reads a lists of lists and all the lists of lists it includes
+fn read_list_of_lists<'a>(
+  url: String,
+  downloader: &'a Downloader,
+  walk: &'a mut Walk,
+) -> LocalBoxFuture<'a, Result<Vec<Source>, FetchError>> {
+  Box::pin(async move {
+    if walk.chain.contains(&url) {
+      return Err(FetchError::IncludeCycle(format!("{} → {}", walk.chain.join(" → "), url)));
     }
-    .trim();
-    if !cleaned.is_empty() && !urls.insert(cleaned.to_string()) {
-      warn!("「{}」 is a duplicate", cleaned);
+    if walk.chain.len() > MAX_INCLUDE_DEPTH {
+      return Err(FetchError::IncludeTooDeep { url, max: MAX_INCLUDE_DEPTH });
     }
-  });
-  if urls.is_empty() { Err("No urls found in file".into()) } else { Ok(urls) }
-}
-
-/// return the number of valid lines
-/// zero would indicate an invalid list
-fn number_of_valid_lines(content: &str) -> usize {
-  content
-    .lines()
-    .filter_map(|line| {
-      let cleaned = line.split('#').next()?.trim();
-      if cleaned.is_empty() { None } else { Some(cleaned.to_string()) }
-    })
-    .filter(|line| {
-      match line.split(' ').next_back() {
-        Some(host) => match addr::parse_dns_name(host) {
-          Ok(_) => Some(host),
-          Err(_) => None,
-        },
-        _ => None,
+    if !walk.included.insert(url.clone()) {
+      info!("「{}」 was already included, skipping it", url);
+      return Ok(Vec::new());
+    }
+    let fetched = download_url(url.clone(), &SourceOptions::default(), downloader).await?;
+    let mut content = fetched.text.map_err(|e| FetchError::Download { url: url.clone(), source: e.into() })?;
+
+    // if the file has a link url at the start, we try to download a fresh instance
+    if let Some(refresh_url) = extract_dns_block_url(&content) {
+      info!("「{}」 has a refresh url in the first line, try to download that", url);
+      if let Ok(r) = download_url(refresh_url.to_string(), &SourceOptions::default(), downloader).await
+        && let Ok(res) = r.text
+      {
+        content = res;
+      }
+    }
+
+    walk.chain.push(url.clone());
+    let mut sources = Vec::new();
+    for line in content.lines() {
+      let skipped = |reason: String| FetchError::InvalidSource { list: url.clone(), line: line.trim().to_string(), reason };
+      if let Some(target) = line.trim().strip_prefix(INCLUDE_PREFIX) {
+        let target = Source { url: target.trim().to_string(), options: SourceOptions::default() };
+        match resolve(target, &url) {
+          Ok(lists) => {
+            for list in lists {
+              match read_list_of_lists(list.url.clone(), downloader, walk).await {
+                Ok(mut included_sources) => sources.append(&mut included_sources),
+                Err(e) => {
+                  warn!("Skipping the include of 「{}」 in 「{}」: {}", list.url, url, e);
+                  walk.failures.push(e);
+                }
+              }
+            }
+          }
+          Err(e) => {
+            let e = skipped(e.to_string());
+            warn!("{}", e);
+            walk.failures.push(e);
+          }
+        }
+        continue;
+      }
+      match Source::parse(line)
+        .and_then(|source| source.map(|source| resolve(source, &url).map_err(|e| e.to_string())).transpose())
+      {
+        Ok(Some(resolved)) => sources.extend(resolved),
+        Ok(None) => {}
+        Err(e) => {
+          let e = skipped(e);
+          warn!("{}", e);
+          walk.failures.push(e);
+        }
       }
-      .is_some()
-    })
-    .count()
+    }
+    walk.chain.pop();
+    Ok(sources)
+  })
+}
+
+/// Resolves a relative entry against the lists of lists it appears in, a local file or a remote url
+/// a relative gpg keyring is always local, next to the lists of lists file or in the working directory
+fn resolve(mut source: Source, parent_url: &str) -> io::Result<Vec<Source>> {
+  match parent_url.strip_prefix("file://") {
+    Some(path) => {
+      let base_dir = Path::new(path).parent().unwrap_or(Path::new("/"));
+      source.options.gpg = source.options.gpg.map(|keyring| base_dir.join(keyring));
+      resolve_local(source, base_dir)
+    }
+    None if !source.url.contains("://") => {
+      let url = Url::parse(parent_url).and_then(|base| base.join(&source.url)).map_err(io::Error::other)?;
+      Ok(vec![Source { url: url.to_string(), options: source.options }])
+    }
+    None => Ok(vec![source]),
+  }
+}
+
+/// Turns paths into file:// urls, relative paths start at the directory of the lists of lists file
+/// a directory stands for all the files in it, remote urls are left alone
+fn resolve_local(source: Source, base_dir: &Path) -> io::Result<Vec<Source>> {
+  if source.url.contains("://") && !source.url.starts_with("file://") {
+    return Ok(vec![source]);
+  }
+  let path = Path::new(source.url.strip_prefix("file://").unwrap_or(&source.url));
+  let path = base_dir.join(path);
+  // canonical, so includes going round in circles through ../ are still noticed
+  let path = fs::canonicalize(&path).or_else(|_| std::path::absolute(&path))?;
+  if !path.is_dir() {
+    return Ok(vec![Source { url: format!("file://{}", path.display()), options: source.options }]);
+  }
+  let mut files: Vec<PathBuf> = fs::read_dir(&path)?
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.path())
+    .filter(|path| path.is_file() && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+    .collect();
+  files.sort();
+  Ok(
+    files.into_iter().map(|file| Source { url: format!("file://{}", file.display()), options: source.options.clone() }).collect(),
+  )
+}
+
+#[cfg(test)]
+mod test1 {
+  #[test]
+  fn extract_dns_block_url() {
+    let content = "#!dns-block https://v.firebog.net/hosts/lists.php?type=nocross";
+    assert_eq!(Some("https://v.firebog.net/hosts/lists.php?type=nocross"), super::extract_dns_block_url(content));
+  }
+
+  #[test]
+  fn resolve_local_test() {
+    use super::{Source, resolve_local};
+
+    let dir = std::env::temp_dir().join(format!("shared-resolve-test-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("mirror")).unwrap();
+    for file in ["b.txt.gz", "a.txt", ".hidden"] {
+      std::fs::write(dir.join("mirror").join(file), "").unwrap();
+    }
+    let urls = |line: &str| -> Vec<String> {
+      let source = Source::parse(line).unwrap().unwrap();
+      resolve_local(source, &dir).unwrap().into_iter().map(|source| source.url).collect()
+    };
+    assert_eq!(vec!["https://example.com/hosts"], urls("https://example.com/hosts"));
+    assert_eq!(vec!["file:///srv/lists/hosts"], urls("file:///srv/lists/hosts"));
+    assert_eq!(vec![format!("file://{}", dir.join("own.txt").display())], urls("own.txt"));
+    assert_eq!(
+      vec![format!("file://{}", dir.join("mirror/a.txt").display()), format!("file://{}", dir.join("mirror/b.txt.gz").display())],
+      urls("mirror tag=mirror")
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod tests_download {
+  use std::io::{BufRead, BufReader, Write};
+  use std::net::TcpListener;
+  use std::thread::{self, JoinHandle};
+
+  use super::{Downloader, FetchError, FetchOptions, SourceOptions, download_url};
+
+  /// Serves one canned response per connection, returns the url and the requests received
+  fn serve(responses: Vec<&'static str>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hosts", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+      responses
+        .into_iter()
+        .map(|response| {
+          let (mut stream, _) = listener.accept().unwrap();
+          let mut request = String::new();
+          let mut reader = BufReader::new(&stream);
+          while reader.read_line(&mut request).unwrap() > 2 {}
+          stream.write_all(response.as_bytes()).unwrap();
+          request.to_lowercase()
+        })
+        .collect()
+    });
+    (url, handle)
+  }
+
+  #[tokio::test]
+  async fn conditional_request_and_fallback_test() {
+    let dir = std::env::temp_dir().join(format!("shared-download-test-{}", std::process::id()));
+    let with_cache = FetchOptions { max_retries: 0, cache_dir: Some(dir.clone()), ..FetchOptions::default() };
+    let downloader = Downloader::new(&with_cache).unwrap();
+    let (url, server) = serve(vec![
+      "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 16\r\nConnection: close\r\n\r\nads.example.com\n",
+      "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+      "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 5\r\nConnection: close\r\n\r\noops\n",
+    ]);
+
+    for _ in 0..3 {
+      let result = download_url(url.clone(), &SourceOptions::default(), &downloader).await.unwrap();
+      assert_eq!("ads.example.com\n", result.text.unwrap());
+    }
+    let requests = server.join().unwrap();
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains("if-none-match: \"v1\""));
+
+    // the server is gone, the cached copy stands in
+    let result = download_url(url.clone(), &SourceOptions::default(), &downloader).await.unwrap();
+    assert_eq!("ads.example.com\n", result.text.unwrap());
+    // without a cache the failure is reported
+    let without_cache = Downloader::new(&FetchOptions { max_retries: 0, ..FetchOptions::default() }).unwrap();
+    assert!(download_url(url, &SourceOptions::default(), &without_cache).await.is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn pinned_list_test() {
+    let dir = std::env::temp_dir().join(format!("shared-pinned-test-{}", std::process::id()));
+    let with_cache = FetchOptions { max_retries: 0, cache_dir: Some(dir.clone()), ..FetchOptions::default() };
+    let downloader = Downloader::new(&with_cache).unwrap();
+    let pinned = SourceOptions {
+      sha256: Some("3d439fc6b959423465db4238e7df7ebda7d47a3a9e123ce899faed5e49e4b1eb".to_string()),
+      ..SourceOptions::default()
+    };
+    let (url, server) = serve(vec![
+      "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 16\r\nConnection: close\r\n\r\nads.example.com\n",
+      "HTTP/1.1 200 OK\r\nContent-Length: 12\r\nConnection: close\r\n\r\nexample.com\n",
+    ]);
+
+    let result = download_url(url.clone(), &pinned, &downloader).await.unwrap();
+    assert_eq!("ads.example.com\n", result.text.unwrap());
+    // the tampered list is rejected, the verified copy from the cache stands in
+    let result = download_url(url.clone(), &pinned, &downloader).await.unwrap();
+    assert_eq!("ads.example.com\n", result.text.unwrap());
+    // pinned lists are never requested conditionally
+    assert!(!server.join().unwrap()[1].contains("if-none-match"));
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn no_valid_content_test() {
+    let dir = std::env::temp_dir().join(format!("shared-invalid-test-{}", std::process::id()));
+    let with_cache = FetchOptions { max_retries: 0, cache_dir: Some(dir.clone()), ..FetchOptions::default() };
+    let downloader = Downloader::new(&with_cache).unwrap();
+    let (url, server) = serve(vec![
+      "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 16\r\nConnection: close\r\n\r\nads.example.com\n",
+      "HTTP/1.1 200 OK\r\nContent-Length: 22\r\nConnection: close\r\n\r\n# this list has moved\n",
+      "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+    ]);
+
+    for _ in 0..3 {
+      let result = download_url(url.clone(), &SourceOptions::default(), &downloader).await.unwrap();
+      assert_eq!("ads.example.com\n", result.text.unwrap());
+    }
+    // the emptied list did not replace the cached copy or its etag
+    assert!(server.join().unwrap()[2].contains("if-none-match: \"v1\""));
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn retry_after_test() {
+    let downloader = Downloader::new(&FetchOptions { max_retries: 1, ..FetchOptions::default() }).unwrap();
+    let (url, server) = serve(vec![
+      "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+      "HTTP/1.1 200 OK\r\nContent-Length: 16\r\nConnection: close\r\n\r\nads.example.com\n",
+    ]);
+    let start = std::time::Instant::now();
+    let result = download_url(url, &SourceOptions::default(), &downloader).await.unwrap();
+    assert_eq!("ads.example.com\n", result.text.unwrap());
+    assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+    assert_eq!(2, server.join().unwrap().len());
+  }
+
+  #[tokio::test]
+  async fn include_test() {
+    use super::get_url_list;
+
+    let dir = std::env::temp_dir().join(format!("shared-include-test-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("vendors")).unwrap();
+    let master = dir.join("master.txt");
+    std::fs::write(&master, "https://example.com/own\n#!include vendors\n#!include missing.txt\n").unwrap();
+    std::fs::write(dir.join("vendors/a.txt"), "https://example.com/a tag=a\n#!include b.txt\n").unwrap();
+    // includes the master again, that's a cycle
+    std::fs::write(dir.join("vendors/b.txt"), "https://example.com/b\n#!include ../master.txt\n").unwrap();
+
+    let downloader = Downloader::new(&FetchOptions::default()).unwrap();
+    let (sources, failures) = get_url_list(&master, &downloader).await;
+    let urls: Vec<&str> = sources.iter().map(|source| source.url.as_str()).collect();
+    // b.txt is read through a.txt, the directory include skips it the second time
+    assert_eq!(vec!["https://example.com/own", "https://example.com/a", "https://example.com/b"], urls);
+    assert_eq!(Some("a".to_string()), sources[1].options.tag);
+    // the cycle and the missing file are skipped, the rest of the lists is still used
+    assert_eq!(2, failures.len());
+    assert!(matches!(failures[0], FetchError::IncludeCycle(_)));
+    assert!(matches!(failures[1], FetchError::Read { .. }));
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn empty_lists_file_test() {
+    use super::fetch_lists;
+
+    let dir = std::env::temp_dir().join(format!("shared-empty-lists-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("empty.txt"), "# nothing here yet\n").unwrap();
+    std::fs::write(dir.join("lists.txt"), "hosts.txt\n").unwrap();
+    std::fs::write(dir.join("hosts.txt"), "ads.example.com\n").unwrap();
+
+    let lists_files = vec![dir.join("empty.txt"), dir.join("lists.txt")];
+    let report = fetch_lists(Some(lists_files), &FetchOptions::default()).await.unwrap();
+    // the empty lists of lists is reported, the lists from the other one are still used
+    assert_eq!(1, report.lists.len());
+    assert_eq!(1, report.failures.len());
+    assert!(matches!(report.failures[0], FetchError::EmptyListsFile(_)));
+    assert!(!report.failures.iter().any(FetchError::is_unreadable_lists_file));
+
+    // one that can't be read stops the run
+    let lists_files = vec![dir.join("missing.txt"), dir.join("lists.txt")];
+    let report = fetch_lists(Some(lists_files), &FetchOptions::default()).await.unwrap();
+    assert!(report.failures.iter().any(FetchError::is_unreadable_lists_file));
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn local_compressed_file_test() {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    let path = std::env::temp_dir().join(format!("shared-local-test-{}.txt.gz", std::process::id()));
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"||ads.example.com^\n").unwrap();
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let downloader = Downloader::new(&FetchOptions::default()).unwrap();
+    let result = download_url(format!("file://{}", path.display()), &SourceOptions::default(), &downloader).await.unwrap();
+    assert_eq!("||ads.example.com^\n", result.text.unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert!(download_url(format!("file://{}", path.display()), &SourceOptions::default(), &downloader).await.is_err());
+  }
 }
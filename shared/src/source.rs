@@ -0,0 +1,173 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::list_format::ListFormat;
+
+/// Response policy for the domains of a source when writing an RPZ zone
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+  /// The domain does not exist
+  #[default]
+  Nxdomain,
+  /// The domain exists but has no records
+  Nodata,
+  /// No answer at all
+  Drop,
+}
+
+impl Action {
+  /// Target of the CNAME record implementing the policy
+  pub fn rpz_target(self) -> &'static str {
+    match self {
+      Action::Nxdomain => ".",
+      Action::Nodata => "*.",
+      Action::Drop => "rpz-drop.",
+    }
+  }
+}
+
+impl fmt::Display for Action {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Action::Nxdomain => "nxdomain",
+      Action::Nodata => "nodata",
+      Action::Drop => "drop",
+    };
+    f.write_str(name)
+  }
+}
+
+impl FromStr for Action {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Action, String> {
+    match s.to_ascii_lowercase().as_str() {
+      "nxdomain" => Ok(Action::Nxdomain),
+      "nodata" => Ok(Action::Nodata),
+      "drop" => Ok(Action::Drop),
+      _ => Err(format!("unknown action 「{}」, expected nxdomain, nodata or drop", s)),
+    }
+  }
+}
+
+/// Settings of a single source, given after the url in a lists of lists file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceOptions {
+  /// Syntax of the list, detected from the content if missing
+  pub format: Option<ListFormat>,
+  pub action: Action,
+  /// Category of the list, e.g. ads or malware
+  pub tag: Option<String>,
+  pub enabled: bool,
+  /// Hex encoded sha256 digest the downloaded list must have
+  pub sha256: Option<String>,
+  /// Base64 minisign public key the detached signature must verify against
+  pub minisign: Option<String>,
+  /// Keyring file for gpgv to verify the detached signature against
+  pub gpg: Option<PathBuf>,
+  /// Where the detached signature is, the url with .minisig or .sig appended if missing
+  pub signature: Option<String>,
+}
+
+impl SourceOptions {
+  /// The list has to be verified before it can be used
+  pub fn is_pinned(&self) -> bool {
+    self.sha256.is_some() || self.minisign.is_some() || self.gpg.is_some()
+  }
+}
+
+impl Default for SourceOptions {
+  fn default() -> SourceOptions {
+    SourceOptions {
+      format: None,
+      action: Action::default(),
+      tag: None,
+      enabled: true,
+      sha256: None,
+      minisign: None,
+      gpg: None,
+      signature: None,
+    }
+  }
+}
+
+/// A line of a lists of lists file, e.g. `https://example.com/hosts format=hosts action=nxdomain tag=ads enabled=true`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+  pub url: String,
+  pub options: SourceOptions,
+}
+
+impl Source {
+  /// Reads a line of a lists of lists file, None for empty and comment lines
+  pub fn parse(line: &str) -> Result<Option<Source>, String> {
+    let cleaned = line.split('#').next().unwrap_or_default();
+    let mut tokens = cleaned.split_whitespace();
+    let Some(url) = tokens.next() else {
+      return Ok(None);
+    };
+    let mut options = SourceOptions::default();
+    for token in tokens {
+      let (key, value) = token.split_once('=').ok_or_else(|| format!("expected key=value, found 「{}」", token))?;
+      match key {
+        "format" => options.format = Some(value.parse()?),
+        "action" => options.action = value.parse()?,
+        "tag" => options.tag = Some(value.to_string()),
+        "enabled" => {
+          options.enabled = value.parse().map_err(|_| format!("enabled should be true or false, found 「{}」", value))?
+        }
+        "sha256" => {
+          if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("sha256 should be 64 hex digits, found 「{}」", value));
+          }
+          options.sha256 = Some(value.to_ascii_lowercase())
+        }
+        "minisign" => options.minisign = Some(value.to_string()),
+        "gpg" => options.gpg = Some(PathBuf::from(value)),
+        "signature" => options.signature = Some(value.to_string()),
+        _ => return Err(format!("unknown option 「{}」", key)),
+      }
+    }
+    Ok(Some(Source { url: url.to_string(), options }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_test() {
+    assert_eq!(Ok(None), Source::parse("# just a comment"));
+    assert_eq!(
+      Ok(Some(Source { url: "https://example.com/hosts".to_string(), options: SourceOptions::default() })),
+      Source::parse("https://example.com/hosts # comment")
+    );
+    let source =
+      Source::parse("https://example.com/abp.txt format=abp action=nodata tag=malware enabled=false").unwrap().unwrap();
+    assert_eq!(
+      SourceOptions {
+        format: Some(ListFormat::Adblock),
+        action: Action::Nodata,
+        tag: Some("malware".to_string()),
+        enabled: false,
+        ..SourceOptions::default()
+      },
+      source.options
+    );
+    let source = Source::parse("https://example.com/hosts minisign=RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3=")
+      .unwrap()
+      .unwrap();
+    assert_eq!(Some("RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3=".to_string()), source.options.minisign);
+    assert!(source.options.is_pinned());
+  }
+
+  #[test]
+  fn parse_error_test() {
+    assert!(Source::parse("https://example.com/hosts format=xml").is_err());
+    assert!(Source::parse("https://example.com/hosts colour=red").is_err());
+    assert!(Source::parse("https://example.com/hosts enabled").is_err());
+    assert!(Source::parse("https://example.com/hosts sha256=abc").is_err());
+  }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use chrono::DateTime;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{StatusCode, Url};
+use reqwest_middleware::Error;
+use reqwest_retry::{DefaultRetryableStrategy, Retryable, RetryableStrategy};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Limits the number of simultaneous requests to a single host
+pub struct HostLimiter {
+  per_host: usize,
+  hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimiter {
+  pub fn new(per_host: usize) -> HostLimiter {
+    HostLimiter { per_host: per_host.max(1), hosts: Mutex::new(HashMap::new()) }
+  }
+
+  /// Waits for a free slot for the host of the url, the slot is released when the permit is dropped
+  pub async fn acquire(&self, url: &str) -> OwnedSemaphorePermit {
+    let host = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)).unwrap_or_default();
+    let semaphore = {
+      let mut hosts = self.hosts.lock().unwrap();
+      Arc::clone(hosts.entry(host).or_insert_with(|| Arc::new(Semaphore::new(self.per_host))))
+    };
+    // the semaphore is never closed
+    semaphore.acquire_owned().await.unwrap()
+  }
+}
+
+/// Reads the Retry-After header, either a number of seconds or an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+  let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+  if let Ok(seconds) = value.parse::<u64>() {
+    return Some(Duration::from_secs(seconds));
+  }
+  let date = DateTime::parse_from_rfc2822(value).ok()?;
+  let date = SystemTime::UNIX_EPOCH + Duration::from_secs(u64::try_from(date.timestamp()).ok()?);
+  Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Server asks us to slow down and tells us for how long
+pub fn is_throttled(status: StatusCode, headers: &HeaderMap) -> bool {
+  (status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE) && headers.contains_key(RETRY_AFTER)
+}
+
+/// The default strategy of the retry middleware, except that throttled responses are handed back
+/// so the caller can wait as long as the server asked instead of the exponential backoff
+pub struct RetryAfterAwareStrategy;
+
+impl RetryableStrategy for RetryAfterAwareStrategy {
+  fn handle(&self, res: &Result<reqwest::Response, Error>) -> Option<Retryable> {
+    match res {
+      Ok(response) if is_throttled(response.status(), response.headers()) => None,
+      _ => DefaultRetryableStrategy.handle(res),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::header::HeaderValue;
+
+  #[test]
+  fn retry_after_test() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(None, retry_after(&headers));
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+    assert_eq!(Some(Duration::from_secs(120)), retry_after(&headers));
+    assert!(is_throttled(StatusCode::TOO_MANY_REQUESTS, &headers));
+    assert!(!is_throttled(StatusCode::INTERNAL_SERVER_ERROR, &headers));
+    // a date in the past means we can go ahead right away
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    assert_eq!(Some(Duration::ZERO), retry_after(&headers));
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+    assert_eq!(None, retry_after(&headers));
+  }
+
+  #[tokio::test]
+  async fn host_limiter_test() {
+    let limiter = HostLimiter::new(1);
+    let permit = limiter.acquire("https://raw.githubusercontent.com/a").await;
+    // another host is not affected
+    let _other = limiter.acquire("https://v.firebog.net/hosts/b").await;
+    let waiting = limiter.acquire("https://raw.githubusercontent.com/c");
+    tokio::pin!(waiting);
+    assert!(tokio::time::timeout(Duration::from_millis(50), &mut waiting).await.is_err());
+    drop(permit);
+    assert!(tokio::time::timeout(Duration::from_millis(50), waiting).await.is_ok());
+  }
+}
@@ -0,0 +1,88 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use minisign_verify::{PublicKey, Signature};
+use sha2::{Digest, Sha256};
+
+/// Lower case hex encoded sha256 digest of bytes, the form lists are pinned with
+pub fn hex_sha256(bytes: &[u8]) -> String {
+  Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compares the body with the pinned hex encoded sha256 digest
+pub fn check_sha256(body: &[u8], pin: &str) -> Result<(), String> {
+  let digest = hex_sha256(body);
+  if digest.eq_ignore_ascii_case(pin) { Ok(()) } else { Err(format!("sha256 is {} instead of the pinned {}", digest, pin)) }
+}
+
+/// Checks a detached minisign signature, public_key is the base64 key as found in the second line of minisign.pub
+pub fn check_minisign(body: &[u8], signature: &[u8], public_key: &str) -> Result<(), String> {
+  let public_key = PublicKey::from_base64(public_key).map_err(|e| format!("invalid minisign public key: {}", e))?;
+  let signature =
+    Signature::decode(&String::from_utf8_lossy(signature)).map_err(|e| format!("invalid minisign signature: {}", e))?;
+  public_key.verify(body, &signature, false).map_err(|e| format!("minisign verification failed: {}", e))
+}
+
+/// Checks a detached GPG signature with gpgv against the keys in keyring
+/// the signature file gets a random name and is created exclusively, readable by the owner only,
+/// so another local user can't point it elsewhere with a symlink or swap in their own signature
+pub fn check_gpg(body: &[u8], signature: &[u8], keyring: &Path) -> Result<(), String> {
+  let stored = |e: std::io::Error| format!("could not store the gpg signature: {}", e);
+  let mut signature_file = tempfile::Builder::new().prefix("dns-block-").suffix(".sig").tempfile().map_err(stored)?;
+  signature_file.write_all(signature).and_then(|_| signature_file.flush()).map_err(stored)?;
+  // removed when dropped
+  run_gpgv(body, signature_file.path(), keyring)
+}
+
+fn run_gpgv(body: &[u8], signature_file: &Path, keyring: &Path) -> Result<(), String> {
+  let keyring = std::path::absolute(keyring).map_err(|e| e.to_string())?;
+  let mut child = Command::new("gpgv")
+    .arg("--keyring")
+    .arg(&keyring)
+    .arg(signature_file)
+    .arg("-")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("could not run gpgv: {}", e))?;
+  // gpgv may stop reading early on a bad signature, the exit status tells what happened
+  if let Some(mut stdin) = child.stdin.take() {
+    let _ = stdin.write_all(body);
+  }
+  let output = child.wait_with_output().map_err(|e| format!("could not run gpgv: {}", e))?;
+  if output.status.success() {
+    Ok(())
+  } else {
+    // the last line has the verdict, e.g. BAD signature from ...
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!("gpg verification failed: {}", stderr.lines().last().unwrap_or_default().trim()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sha256_test() {
+    let pin = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+    assert_eq!(Ok(()), check_sha256(b"test", pin));
+    assert_eq!(Ok(()), check_sha256(b"test", &pin.to_uppercase()));
+    assert!(check_sha256(b"test\n", pin).is_err());
+  }
+
+  #[test]
+  fn minisign_test() {
+    // test vector of the minisign-verify crate
+    let public_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    let signature = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1633700835\tfile:test\tprehashed
+wLMDjy9FLAuxZ3q4NlEvkgtyhrr0gtTu6KC4KBJdITbbOeAi1zBIYo0v4iTgt8jJpIidRJnp94ABQkJAgAooBQ==";
+    assert_eq!(Ok(()), check_minisign(b"test", signature.as_bytes(), public_key));
+    assert!(check_minisign(b"tampered", signature.as_bytes(), public_key).is_err());
+    assert!(check_minisign(b"test", b"garbage", public_key).is_err());
+  }
+}
@@ -0,0 +1,389 @@
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use log::warn;
+
+use crate::error::ZoneError;
+
+/// $INCLUDE nesting deeper than this is most likely a file including itself
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+const CLASSES: [&str; 4] = ["IN", "CH", "HS", "CS"];
+
+/// Record types whose data holds domain names, with the position of the names, relative names get the origin appended
+const NAME_FIELDS: [(&str, &[usize]); 7] =
+  [("PTR", &[0]), ("CNAME", &[0]), ("NS", &[0]), ("DNAME", &[0]), ("MX", &[1]), ("SRV", &[3]), ("SOA", &[0, 1])];
+
+/// A resource record with every name made absolute, without the trailing dot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneRecord {
+  pub name: String,
+  /// Explicit or from $TTL, None if the zone gives neither
+  pub ttl: Option<u32>,
+  pub class: String,
+  /// Upper case, e.g. PTR
+  pub rtype: String,
+  pub data: Vec<String>,
+}
+
+/// Reads a zone file and the files it includes, origin is the name of the zone as in named.conf
+/// included files are looked up next to the zone file
+pub fn read_zone(path: &Path, origin: &str) -> Result<Vec<ZoneRecord>, ZoneError> {
+  let mut reader = ZoneReader::default();
+  reader.read_file(path, &absolute(origin, ""), 0)?;
+  Ok(reader.records)
+}
+
+/// Parses the content of a zone file, included files are looked up in the working directory
+pub fn parse_zone(content: &str, origin: &str) -> Result<Vec<ZoneRecord>, ZoneError> {
+  let mut reader = ZoneReader::default();
+  reader.parse(content, "-", Path::new("."), &absolute(origin, ""), 0)?;
+  Ok(reader.records)
+}
+
+/// The address a PTR record in in-addr.arpa or ip6.arpa is about, None for anything else
+pub fn reverse_name_to_ip(name: &str) -> Option<IpAddr> {
+  let name = name.trim_end_matches('.').to_ascii_lowercase();
+  if let Some(octets) = name.strip_suffix(".in-addr.arpa") {
+    let octets: Vec<u8> = octets.split('.').rev().map(|octet| octet.parse().ok()).collect::<Option<_>>()?;
+    let octets: [u8; 4] = octets.try_into().ok()?;
+    return Some(IpAddr::V4(Ipv4Addr::from(octets)));
+  }
+  let nibbles = name.strip_suffix(".ip6.arpa")?;
+  let nibbles: Vec<u8> = nibbles
+    .split('.')
+    .rev()
+    .map(|nibble| if nibble.len() == 1 { u8::from_str_radix(nibble, 16).ok() } else { None })
+    .collect::<Option<_>>()?;
+  if nibbles.len() != 32 {
+    return None;
+  }
+  let bits = nibbles.iter().fold(0u128, |bits, &nibble| (bits << 4) | nibble as u128);
+  Some(IpAddr::V6(Ipv6Addr::from(bits)))
+}
+
+/// One line of the zone file, or several lines joined by parentheses
+struct Entry {
+  line: usize,
+  /// Starts with white space, the owner is the one of the previous record
+  indented: bool,
+  tokens: Vec<String>,
+}
+
+#[derive(Default)]
+struct ZoneReader {
+  records: Vec<ZoneRecord>,
+}
+
+impl ZoneReader {
+  fn read_file(&mut self, path: &Path, origin: &str, depth: usize) -> Result<(), ZoneError> {
+    let content = fs::read_to_string(path).map_err(|source| ZoneError::Read { path: path.to_path_buf(), source })?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    self.parse(&content, &path.display().to_string(), dir, origin, depth)
+  }
+
+  fn parse(&mut self, content: &str, file: &str, dir: &Path, origin: &str, depth: usize) -> Result<(), ZoneError> {
+    let mut origin = origin.to_string();
+    let mut default_ttl = None;
+    let mut last_owner: Option<String> = None;
+    for entry in entries(content, file)? {
+      let syntax = |message: String| ZoneError::Syntax { file: file.to_string(), line: entry.line, message };
+      let first = entry.tokens[0].as_str();
+      if !entry.indented && first.starts_with('$') {
+        let argument = entry.tokens.get(1).ok_or_else(|| syntax(format!("{} without an argument", first)))?;
+        match first.to_ascii_uppercase().as_str() {
+          "$ORIGIN" => origin = absolute(argument, &origin),
+          "$TTL" => default_ttl = Some(parse_ttl(argument).ok_or_else(|| syntax(format!("invalid ttl 「{}」", argument)))?),
+          "$INCLUDE" => {
+            if depth >= MAX_INCLUDE_DEPTH {
+              return Err(syntax(format!("included more than {} levels deep", MAX_INCLUDE_DEPTH)));
+            }
+            let include_origin = entry.tokens.get(2).map_or(origin.clone(), |name| absolute(name, &origin));
+            self.read_file(&dir.join(argument), &include_origin, depth + 1)?;
+          }
+          "$GENERATE" => {
+            for tokens in generate(&entry.tokens[1..]).map_err(syntax)? {
+              let owner = absolute(&tokens[0], &origin);
+              let record = record(owner.clone(), &tokens[1..], default_ttl, &origin).map_err(syntax)?;
+              self.records.push(record);
+              last_owner = Some(owner);
+            }
+          }
+          _ => warn!("Ignoring the unknown directive 「{}」 in {} line {}", first, file, entry.line),
+        }
+        continue;
+      }
+      let (owner, rest) = if entry.indented {
+        let owner = last_owner.clone().ok_or_else(|| syntax("the first record has no owner".to_string()))?;
+        (owner, &entry.tokens[..])
+      } else {
+        (absolute(first, &origin), &entry.tokens[1..])
+      };
+      self.records.push(record(owner.clone(), rest, default_ttl, &origin).map_err(syntax)?);
+      last_owner = Some(owner);
+    }
+    Ok(())
+  }
+}
+
+/// Builds a record from what follows the owner: [ttl] [class] type data, ttl and class in any order
+fn record(name: String, tokens: &[String], default_ttl: Option<u32>, origin: &str) -> Result<ZoneRecord, String> {
+  let mut ttl = None;
+  let mut class = None;
+  let mut rest = tokens.iter();
+  let rtype = loop {
+    let token = rest.next().ok_or_else(|| format!("「{}」 has no record type", name))?;
+    if class.is_none() && CLASSES.contains(&token.to_ascii_uppercase().as_str()) {
+      class = Some(token.to_ascii_uppercase());
+    } else if ttl.is_none()
+      && let Some(value) = parse_ttl(token)
+    {
+      ttl = Some(value);
+    } else {
+      break token.to_ascii_uppercase();
+    }
+  };
+  let mut data: Vec<String> = rest.cloned().collect();
+  if let Some((_, positions)) = NAME_FIELDS.iter().find(|(name_type, _)| *name_type == rtype) {
+    for &position in positions.iter() {
+      if let Some(field) = data.get_mut(position) {
+        *field = absolute(field, origin);
+      }
+    }
+  }
+  Ok(ZoneRecord { name, ttl: ttl.or(default_ttl), class: class.unwrap_or_else(|| "IN".to_string()), rtype, data })
+}
+
+/// `@` is the origin, names without a trailing dot are relative to it
+fn absolute(name: &str, origin: &str) -> String {
+  if name == "@" {
+    origin.to_string()
+  } else if let Some(name) = name.strip_suffix('.') {
+    name.to_string()
+  } else if origin.is_empty() {
+    name.to_string()
+  } else {
+    format!("{}.{}", name, origin)
+  }
+}
+
+/// Seconds, or a BIND style duration like 1h30m
+fn parse_ttl(token: &str) -> Option<u32> {
+  if !token.starts_with(|c: char| c.is_ascii_digit()) {
+    return None;
+  }
+  if let Ok(seconds) = token.parse() {
+    return Some(seconds);
+  }
+  let mut total: u32 = 0;
+  let mut number = String::new();
+  for c in token.chars() {
+    if c.is_ascii_digit() {
+      number.push(c);
+      continue;
+    }
+    let unit = match c.to_ascii_lowercase() {
+      's' => 1,
+      'm' => 60,
+      'h' => 3600,
+      'd' => 86400,
+      'w' => 604800,
+      _ => return None,
+    };
+    total = total.checked_add(number.parse::<u32>().ok()?.checked_mul(unit)?)?;
+    number.clear();
+  }
+  if number.is_empty() { Some(total) } else { None }
+}
+
+/// Splits the zone into entries, dropping comments and joining the lines inside parentheses
+fn entries(content: &str, file: &str) -> Result<Vec<Entry>, ZoneError> {
+  let mut entries = Vec::new();
+  let mut current: Option<Entry> = None;
+  let mut depth = 0;
+  for (index, line) in content.lines().enumerate() {
+    let entry = current.get_or_insert_with(|| Entry {
+      line: index + 1,
+      indented: line.starts_with(char::is_whitespace),
+      tokens: Vec::new(),
+    });
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+      match c {
+        '\\' => {
+          token.push(c);
+          token.extend(chars.next());
+        }
+        '"' => quoted = !quoted,
+        _ if quoted => token.push(c),
+        ';' => break,
+        '(' | ')' => {
+          depth += if c == '(' { 1 } else { -1 };
+          if depth < 0 {
+            return Err(ZoneError::Syntax { file: file.to_string(), line: index + 1, message: "unbalanced 「)」".to_string() });
+          }
+          entry.tokens.extend((!token.is_empty()).then(|| std::mem::take(&mut token)));
+        }
+        _ if c.is_whitespace() => entry.tokens.extend((!token.is_empty()).then(|| std::mem::take(&mut token))),
+        _ => token.push(c),
+      }
+    }
+    entry.tokens.extend((!token.is_empty()).then_some(token));
+    if depth == 0 {
+      let entry = current.take().expect("an entry is being read");
+      if !entry.tokens.is_empty() {
+        entries.push(entry);
+      }
+    }
+  }
+  if depth > 0 {
+    let line = current.map_or(0, |entry| entry.line);
+    return Err(ZoneError::Syntax { file: file.to_string(), line, message: "unbalanced 「(」".to_string() });
+  }
+  Ok(entries)
+}
+
+/// Expands `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs` into the tokens of the records
+fn generate(tokens: &[String]) -> Result<Vec<Vec<String>>, String> {
+  let range = tokens.first().ok_or("$GENERATE without a range")?;
+  let (range, step) = range.split_once('/').unwrap_or((range, "1"));
+  let (start, stop) = range.split_once('-').ok_or_else(|| format!("invalid range 「{}」", range))?;
+  let invalid = |_| format!("invalid range 「{}」", range);
+  let (start, stop, step): (u32, u32, u32) =
+    (start.parse().map_err(invalid)?, stop.parse().map_err(invalid)?, step.parse().map_err(invalid)?);
+  if step == 0 || start > stop {
+    return Err(format!("invalid range 「{}」", range));
+  }
+  if tokens.len() < 4 {
+    return Err("$GENERATE needs a range, an owner, a type and data".to_string());
+  }
+  (start..=stop)
+    .step_by(step as usize)
+    .map(|i| tokens[1..].iter().map(|token| substitute(token, i)).collect::<Result<Vec<String>, String>>())
+    .collect()
+}
+
+/// Replaces `$` with the iterator and `${offset,width,base}` with the formatted iterator, `\$` is a literal dollar
+fn substitute(token: &str, i: u32) -> Result<String, String> {
+  let mut out = String::new();
+  let mut chars = token.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => out.extend(chars.next()),
+      '$' if chars.peek() == Some(&'{') => {
+        chars.next();
+        let modifier: String = chars.by_ref().take_while(|&c| c != '}').collect();
+        let mut parts = modifier.split(',');
+        let invalid = || format!("invalid modifier 「${{{}}}」", modifier);
+        let offset: i64 = parts.next().unwrap_or("0").parse().map_err(|_| invalid())?;
+        let width: usize = parts.next().unwrap_or("0").parse().map_err(|_| invalid())?;
+        let value = i as i64 + offset;
+        let formatted = match parts.next().unwrap_or("d") {
+          "d" => format!("{:0width$}", value),
+          "o" => format!("{:0width$o}", value),
+          "x" => format!("{:0width$x}", value),
+          "X" => format!("{:0width$X}", value),
+          _ => return Err(invalid()),
+        };
+        out.push_str(&formatted);
+      }
+      '$' => out.push_str(&i.to_string()),
+      _ => out.push(c),
+    }
+  }
+  Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::IpAddr;
+
+  use super::*;
+
+  fn ptr(records: &[ZoneRecord]) -> Vec<(String, String)> {
+    records.iter().filter(|r| r.rtype == "PTR").map(|r| (r.name.clone(), r.data[0].clone())).collect()
+  }
+
+  #[test]
+  fn reverse_zone_test() {
+    let zone = indoc::indoc! {"
+      $TTL 1d
+      @   IN SOA ns.lan. admin.lan. (
+                 2024010101 ; serial
+                 1h 15m 1w 1h )
+          IN NS  ns.lan.
+      1   IN PTR router.lan.
+      2   3600 PTR nas.lan. ; ttl without class
+      $ORIGIN 1.0.10.in-addr.arpa.
+      5   PTR printer
+    "};
+    let records = parse_zone(zone, "0.0.10.in-addr.arpa").unwrap();
+    assert_eq!("0.0.10.in-addr.arpa", records[0].name);
+    assert_eq!(vec!["ns.lan", "admin.lan", "2024010101", "1h", "15m", "1w", "1h"], records[0].data);
+    assert_eq!(Some(86400), records[0].ttl);
+    // the NS record inherits the owner of the SOA record
+    assert_eq!(("0.0.10.in-addr.arpa", "NS"), (records[1].name.as_str(), records[1].rtype.as_str()));
+    assert_eq!(Some(3600), records[3].ttl);
+    assert_eq!(
+      vec![
+        ("1.0.0.10.in-addr.arpa".to_string(), "router.lan".to_string()),
+        ("2.0.0.10.in-addr.arpa".to_string(), "nas.lan".to_string()),
+        ("5.1.0.10.in-addr.arpa".to_string(), "printer.1.0.10.in-addr.arpa".to_string()),
+      ],
+      ptr(&records)
+    );
+  }
+
+  #[test]
+  fn generate_test() {
+    let records = parse_zone("$GENERATE 10-30/10 $ PTR dhcp-${0,3,d}.lan.\n", "0.0.10.in-addr.arpa").unwrap();
+    assert_eq!(
+      vec![
+        ("10.0.0.10.in-addr.arpa".to_string(), "dhcp-010.lan".to_string()),
+        ("20.0.0.10.in-addr.arpa".to_string(), "dhcp-020.lan".to_string()),
+        ("30.0.0.10.in-addr.arpa".to_string(), "dhcp-030.lan".to_string()),
+      ],
+      ptr(&records)
+    );
+  }
+
+  #[test]
+  fn include_test() {
+    let dir = std::env::temp_dir().join(format!("shared-zone-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("db.0.0.10"), "$TTL 60\n1 PTR router.lan.\n$INCLUDE hosts.inc\n3 PTR laptop.lan.\n").unwrap();
+    std::fs::write(dir.join("hosts.inc"), "$ORIGIN lan.\n2 PTR elsewhere\n").unwrap();
+    let records = read_zone(&dir.join("db.0.0.10"), "0.0.10.in-addr.arpa.").unwrap();
+    // the origin set in the included file does not leak back into the including file
+    assert_eq!(
+      vec![
+        ("1.0.0.10.in-addr.arpa".to_string(), "router.lan".to_string()),
+        ("2.lan".to_string(), "elsewhere.lan".to_string()),
+        ("3.0.0.10.in-addr.arpa".to_string(), "laptop.lan".to_string()),
+      ],
+      ptr(&records)
+    );
+    assert!(matches!(read_zone(&dir.join("missing"), "lan"), Err(ZoneError::Read { .. })));
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn syntax_error_test() {
+    assert!(matches!(parse_zone("1 PTR (router.lan.\n", "lan"), Err(ZoneError::Syntax { line: 1, .. })));
+    assert!(matches!(parse_zone("ok A 10.0.0.1\nbroken\n", "lan"), Err(ZoneError::Syntax { line: 2, .. })));
+  }
+
+  #[test]
+  fn reverse_name_to_ip_test() {
+    assert_eq!(Some("10.0.0.1".parse::<IpAddr>().unwrap()), reverse_name_to_ip("1.0.0.10.in-addr.arpa."));
+    assert_eq!(
+      Some("2001:db8::1".parse::<IpAddr>().unwrap()),
+      reverse_name_to_ip("1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa")
+    );
+    assert_eq!(None, reverse_name_to_ip("0.0.10.in-addr.arpa"));
+    assert_eq!(None, reverse_name_to_ip("router.lan"));
+  }
+}
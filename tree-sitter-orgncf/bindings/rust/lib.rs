@@ -5,6 +5,11 @@
 //!
 //! ```
 //! let code = r#"
+//! 10.0.0.1 router
+//! domain lan
+//! DNS_PREFIX """ """
+//! DNS_SUFFIX """ """
+//! DHCP_PREFIX """ """
 //! "#;
 //! let mut parser = tree_sitter::Parser::new();
 //! let language = tree_sitter_orgncf::LANGUAGE;
@@ -48,4 +53,47 @@ mod tests {
             .set_language(&super::LANGUAGE.into())
             .expect("Error loading Organizator Network Configuration parser");
     }
+
+    /// The examples of a test/corpus file as name, code and expected tree, the format `tree-sitter test` reads
+    fn corpus(text: &str) -> Vec<(String, String, String)> {
+        let mut examples = Vec::new();
+        let mut lines = text.lines().peekable();
+        while let Some(line) = lines.next() {
+            if !line.starts_with("===") {
+                continue;
+            }
+            let name = lines.next().unwrap_or_default().trim().to_string();
+            lines.next();
+            let code: Vec<&str> = lines.by_ref().take_while(|line| !line.starts_with("---")).collect();
+            let mut expected = Vec::new();
+            while let Some(line) = lines.next_if(|line| !line.starts_with("===")) {
+                expected.push(line);
+            }
+            // the tree is compared without its line breaks and indentation
+            let expected = expected.join(" ").split_whitespace().collect::<Vec<_>>().join(" ").replace(" )", ")");
+            examples.push((name, code.join("\n").trim_end().to_string(), expected));
+        }
+        examples
+    }
+
+    fn check_corpus(text: &str) {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&super::LANGUAGE.into()).unwrap();
+        let examples = corpus(text);
+        assert!(!examples.is_empty());
+        for (name, code, expected) in examples {
+            let tree = parser.parse(&code, None).unwrap();
+            assert_eq!(expected, tree.root_node().to_sexp(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_ipv6_corpus() {
+        check_corpus(include_str!("../../test/corpus/ipv6.txt"));
+    }
+
+    #[test]
+    fn test_subnet_corpus() {
+        check_corpus(include_str!("../../test/corpus/subnet.txt"));
+    }
 }
@@ -28,22 +28,35 @@ module.exports = grammar({
         $.domain, 
         $.dns_file_name, 
         $.reverse_dns_file_name, 
-        $.dhcp_file_name
+        $.dhcp_file_name,
+        $.ipv6_prefix,
+        $.reverse_ipv6_dns_file_name,
+        $.dhcpv6_file_name,
+        $.subnet
       ),
     domain:                $ => seq($.domain_keyword, $.hostname, "\n"),
     dns_file_name:         $ => seq($.dns_file_name_keyword, $.filename, "\n"),
     reverse_dns_file_name: $ => seq($.reverse_dns_file_name_keyword, $.filename, "\n"),
     dhcp_file_name:        $ => seq($.dhcp_file_name_keyword, $.filename, "\n"),
+    ipv6_prefix:           $ => seq($.ipv6_prefix_keyword, $.ipv6_network, "\n"),
+    reverse_ipv6_dns_file_name: $ => seq($.reverse_ipv6_dns_file_name_keyword, $.filename, "\n"),
+    dhcpv6_file_name:      $ => seq($.dhcpv6_file_name_keyword, $.filename, "\n"),
+    subnet:                $ => seq($.subnet_keyword, $.ipv4_network, "\n"),
     
     domain_keyword: ($) => /domain/,
     dns_file_name_keyword: ($) => /dns_file_name/,
     reverse_dns_file_name_keyword: ($) => /reverse_dns_file_name/,
     dhcp_file_name_keyword: ($) => /dhcp_file_name/,
+    ipv6_prefix_keyword: ($) => /ipv6_prefix/,
+    reverse_ipv6_dns_file_name_keyword: ($) => /reverse_ipv6_dns_file_name/,
+    dhcpv6_file_name_keyword: ($) => /dhcpv6_file_name/,
+    subnet_keyword: ($) => /subnet/,
 
     _rule: ($) =>
       seq(
         optional($.mac_address),
         $.ip_address,
+        repeat($.ipv6_address),
         repeat1($.hostname),
         "\n",
       ),
@@ -61,6 +74,10 @@ module.exports = grammar({
     ),
     mac_address: ($) => /([0-9A-Fa-f]{2}[:-]){5}([0-9A-Fa-f]{2})/,
     ip_address: ($) => /(\d{1,3}\.){3}\d{1,3}/,
+    ipv4_network: ($) => /(\d{1,3}\.){3}\d{1,3}\/\d{1,2}/,
+    // a full address, or only the interface ID, e.g. ::5, completed with the ipv6_prefix
+    ipv6_address: ($) => /[0-9A-Fa-f]*:[0-9A-Fa-f:.]*/,
+    ipv6_network: ($) => /[0-9A-Fa-f:]*:[0-9A-Fa-f:]*\/\d{1,3}/,
     hostname: ($) => /([a-zA-Z0-9.-]+)|@/,
     comment: ($) => /;[^\n]*/,
     filename: ($) => /[a-zA-Z0-9._-]+/,
@@ -2,6 +2,9 @@
 
 (comment) @comment
 (ip_address) @constant
+(ipv4_network) @constant
+(ipv6_address) @constant
+(ipv6_network) @constant
 (mac_address) @type
 (string_content) @string
 (string_delimiter) @tag.delimiter
@@ -9,6 +12,10 @@
 (dns_file_name_keyword) @keyword
 (reverse_dns_file_name_keyword) @keyword
 (dhcp_file_name_keyword) @keyword
+(ipv6_prefix_keyword) @keyword
+(reverse_ipv6_dns_file_name_keyword) @keyword
+(dhcpv6_file_name_keyword) @keyword
+(subnet_keyword) @keyword
 (dns_prefix_keyword) @keyword
 (dns_suffix_keyword) @keyword
 (dhcp_prefix_keyword) @keyword
@@ -7,14 +7,14 @@
 #endif
 
 #define LANGUAGE_VERSION 15
-#define STATE_COUNT 44
+#define STATE_COUNT 62
 #define LARGE_STATE_COUNT 4
-#define SYMBOL_COUNT 30
+#define SYMBOL_COUNT 42
 #define ALIAS_COUNT 0
-#define TOKEN_COUNT 16
+#define TOKEN_COUNT 23
 #define EXTERNAL_TOKEN_COUNT 0
 #define FIELD_COUNT 0
-#define MAX_ALIAS_SEQUENCE_LENGTH 4
+#define MAX_ALIAS_SEQUENCE_LENGTH 5
 #define MAX_RESERVED_WORD_SET_SIZE 0
 #define PRODUCTION_ID_COUNT 1
 #define SUPERTYPE_COUNT 0
@@ -25,30 +25,42 @@ enum ts_symbol_identifiers {
   sym_dns_file_name_keyword = 3,
   sym_reverse_dns_file_name_keyword = 4,
   sym_dhcp_file_name_keyword = 5,
-  sym_mac_address = 6,
-  sym_ip_address = 7,
-  sym_hostname = 8,
-  sym_comment = 9,
-  sym_filename = 10,
-  sym_dns_prefix_keyword = 11,
-  sym_dns_suffix_keyword = 12,
-  sym_dhcp_prefix_keyword = 13,
-  sym_string_content = 14,
-  sym_string_delimiter = 15,
-  sym_source_file = 16,
-  sym_ip_config_line = 17,
-  sym__extra_config_line = 18,
-  sym_domain = 19,
-  sym_dns_file_name = 20,
-  sym_reverse_dns_file_name = 21,
-  sym_dhcp_file_name = 22,
-  sym__rule = 23,
-  sym_dns_prefix_section = 24,
-  sym_dns_suffix_section = 25,
-  sym_dhcp_prefix_section = 26,
-  sym_string = 27,
-  aux_sym_source_file_repeat1 = 28,
-  aux_sym__rule_repeat1 = 29,
+  sym_ipv6_prefix_keyword = 6,
+  sym_reverse_ipv6_dns_file_name_keyword = 7,
+  sym_dhcpv6_file_name_keyword = 8,
+  sym_subnet_keyword = 9,
+  sym_mac_address = 10,
+  sym_ip_address = 11,
+  sym_ipv4_network = 12,
+  sym_ipv6_address = 13,
+  sym_ipv6_network = 14,
+  sym_hostname = 15,
+  sym_comment = 16,
+  sym_filename = 17,
+  sym_dns_prefix_keyword = 18,
+  sym_dns_suffix_keyword = 19,
+  sym_dhcp_prefix_keyword = 20,
+  sym_string_content = 21,
+  sym_string_delimiter = 22,
+  sym_source_file = 23,
+  sym_ip_config_line = 24,
+  sym__extra_config_line = 25,
+  sym_domain = 26,
+  sym_dns_file_name = 27,
+  sym_reverse_dns_file_name = 28,
+  sym_dhcp_file_name = 29,
+  sym_ipv6_prefix = 30,
+  sym_reverse_ipv6_dns_file_name = 31,
+  sym_dhcpv6_file_name = 32,
+  sym_subnet = 33,
+  sym__rule = 34,
+  sym_dns_prefix_section = 35,
+  sym_dns_suffix_section = 36,
+  sym_dhcp_prefix_section = 37,
+  sym_string = 38,
+  aux_sym_source_file_repeat1 = 39,
+  aux_sym__rule_repeat1 = 40,
+  aux_sym__rule_repeat2 = 41,
 };
 
 static const char * const ts_symbol_names[] = {
@@ -58,8 +70,15 @@ static const char * const ts_symbol_names[] = {
   [sym_dns_file_name_keyword] = "dns_file_name_keyword",
   [sym_reverse_dns_file_name_keyword] = "reverse_dns_file_name_keyword",
   [sym_dhcp_file_name_keyword] = "dhcp_file_name_keyword",
+  [sym_ipv6_prefix_keyword] = "ipv6_prefix_keyword",
+  [sym_reverse_ipv6_dns_file_name_keyword] = "reverse_ipv6_dns_file_name_keyword",
+  [sym_dhcpv6_file_name_keyword] = "dhcpv6_file_name_keyword",
+  [sym_subnet_keyword] = "subnet_keyword",
   [sym_mac_address] = "mac_address",
   [sym_ip_address] = "ip_address",
+  [sym_ipv4_network] = "ipv4_network",
+  [sym_ipv6_address] = "ipv6_address",
+  [sym_ipv6_network] = "ipv6_network",
   [sym_hostname] = "hostname",
   [sym_comment] = "comment",
   [sym_filename] = "filename",
@@ -75,6 +94,10 @@ static const char * const ts_symbol_names[] = {
   [sym_dns_file_name] = "dns_file_name",
   [sym_reverse_dns_file_name] = "reverse_dns_file_name",
   [sym_dhcp_file_name] = "dhcp_file_name",
+  [sym_ipv6_prefix] = "ipv6_prefix",
+  [sym_reverse_ipv6_dns_file_name] = "reverse_ipv6_dns_file_name",
+  [sym_dhcpv6_file_name] = "dhcpv6_file_name",
+  [sym_subnet] = "subnet",
   [sym__rule] = "_rule",
   [sym_dns_prefix_section] = "dns_prefix_section",
   [sym_dns_suffix_section] = "dns_suffix_section",
@@ -82,6 +105,7 @@ static const char * const ts_symbol_names[] = {
   [sym_string] = "string",
   [aux_sym_source_file_repeat1] = "source_file_repeat1",
   [aux_sym__rule_repeat1] = "_rule_repeat1",
+  [aux_sym__rule_repeat2] = "_rule_repeat2",
 };
 
 static const TSSymbol ts_symbol_map[] = {
@@ -91,8 +115,15 @@ static const TSSymbol ts_symbol_map[] = {
   [sym_dns_file_name_keyword] = sym_dns_file_name_keyword,
   [sym_reverse_dns_file_name_keyword] = sym_reverse_dns_file_name_keyword,
   [sym_dhcp_file_name_keyword] = sym_dhcp_file_name_keyword,
+  [sym_ipv6_prefix_keyword] = sym_ipv6_prefix_keyword,
+  [sym_reverse_ipv6_dns_file_name_keyword] = sym_reverse_ipv6_dns_file_name_keyword,
+  [sym_dhcpv6_file_name_keyword] = sym_dhcpv6_file_name_keyword,
+  [sym_subnet_keyword] = sym_subnet_keyword,
   [sym_mac_address] = sym_mac_address,
   [sym_ip_address] = sym_ip_address,
+  [sym_ipv4_network] = sym_ipv4_network,
+  [sym_ipv6_address] = sym_ipv6_address,
+  [sym_ipv6_network] = sym_ipv6_network,
   [sym_hostname] = sym_hostname,
   [sym_comment] = sym_comment,
   [sym_filename] = sym_filename,
@@ -108,6 +139,10 @@ static const TSSymbol ts_symbol_map[] = {
   [sym_dns_file_name] = sym_dns_file_name,
   [sym_reverse_dns_file_name] = sym_reverse_dns_file_name,
   [sym_dhcp_file_name] = sym_dhcp_file_name,
+  [sym_ipv6_prefix] = sym_ipv6_prefix,
+  [sym_reverse_ipv6_dns_file_name] = sym_reverse_ipv6_dns_file_name,
+  [sym_dhcpv6_file_name] = sym_dhcpv6_file_name,
+  [sym_subnet] = sym_subnet,
   [sym__rule] = sym__rule,
   [sym_dns_prefix_section] = sym_dns_prefix_section,
   [sym_dns_suffix_section] = sym_dns_suffix_section,
@@ -115,6 +150,7 @@ static const TSSymbol ts_symbol_map[] = {
   [sym_string] = sym_string,
   [aux_sym_source_file_repeat1] = aux_sym_source_file_repeat1,
   [aux_sym__rule_repeat1] = aux_sym__rule_repeat1,
+  [aux_sym__rule_repeat2] = aux_sym__rule_repeat2,
 };
 
 static const TSSymbolMetadata ts_symbol_metadata[] = {
@@ -142,6 +178,22 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
+  [sym_ipv6_prefix_keyword] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_reverse_ipv6_dns_file_name_keyword] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_dhcpv6_file_name_keyword] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_subnet_keyword] = {
+    .visible = true,
+    .named = true,
+  },
   [sym_mac_address] = {
     .visible = true,
     .named = true,
@@ -150,6 +202,18 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
+  [sym_ipv4_network] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_ipv6_address] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_ipv6_network] = {
+    .visible = true,
+    .named = true,
+  },
   [sym_hostname] = {
     .visible = true,
     .named = true,
@@ -210,6 +274,22 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = true,
     .named = true,
   },
+  [sym_ipv6_prefix] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_reverse_ipv6_dns_file_name] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_dhcpv6_file_name] = {
+    .visible = true,
+    .named = true,
+  },
+  [sym_subnet] = {
+    .visible = true,
+    .named = true,
+  },
   [sym__rule] = {
     .visible = false,
     .named = true,
@@ -238,6 +318,10 @@ static const TSSymbolMetadata ts_symbol_metadata[] = {
     .visible = false,
     .named = false,
   },
+  [aux_sym__rule_repeat2] = {
+    .visible = false,
+    .named = false,
+  },
 };
 
 static const TSSymbol ts_alias_sequences[PRODUCTION_ID_COUNT][MAX_ALIAS_SEQUENCE_LENGTH] = {
@@ -293,6 +377,24 @@ static const TSStateId ts_primary_state_ids[STATE_COUNT] = {
   [41] = 23,
   [42] = 32,
   [43] = 31,
+  [44] = 44,
+  [45] = 45,
+  [46] = 46,
+  [47] = 47,
+  [48] = 48,
+  [49] = 49,
+  [50] = 50,
+  [51] = 51,
+  [52] = 52,
+  [53] = 53,
+  [54] = 54,
+  [55] = 55,
+  [56] = 56,
+  [57] = 57,
+  [58] = 58,
+  [59] = 59,
+  [60] = 60,
+  [61] = 61,
 };
 
 static bool ts_lex(TSLexer *lexer, TSStateId state) {
@@ -311,6 +413,8 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
       if (('A' <= lookahead && lookahead <= 'F') ||
           ('a' <= lookahead && lookahead <= 'f')) ADVANCE(94);
       if (('0' <= lookahead && lookahead <= '9')) ADVANCE(6);
+      if (lookahead == 'i') ADVANCE(138);
+      if (lookahead == 's') ADVANCE(198);
       END_STATE();
     case 1:
       if (lookahead == '\n') ADVANCE(107);
@@ -480,6 +584,7 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
       END_STATE();
     case 43:
       if (lookahead == '_') ADVANCE(61);
+      if (lookahead == 'v') ADVANCE(167);
       END_STATE();
     case 44:
       if (lookahead == '_') ADVANCE(62);
@@ -501,6 +606,7 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
       END_STATE();
     case 50:
       if (lookahead == 'd') ADVANCE(79);
+      if (lookahead == 'i') ADVANCE(149);
       END_STATE();
     case 51:
       if (lookahead == 'e') ADVANCE(85);
@@ -795,6 +901,283 @@ static bool ts_lex(TSLexer *lexer, TSStateId state) {
     case 127:
       ACCEPT_TOKEN(sym_string_delimiter);
       END_STATE();
+    case 128:
+      if (lookahead == ':') ADVANCE(130);
+      if (lookahead == ';') ADVANCE(120);
+      if (lookahead == '@') ADVANCE(116);
+      if (('\t' <= lookahead && lookahead <= '\r') ||
+          lookahead == ' ') SKIP(128);
+      if (('0' <= lookahead && lookahead <= '9') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(129);
+      if (lookahead == '-' ||
+          lookahead == '.' ||
+          ('G' <= lookahead && lookahead <= 'Z') ||
+          ('g' <= lookahead && lookahead <= 'z')) ADVANCE(117);
+      END_STATE();
+    case 129:
+      ACCEPT_TOKEN(sym_hostname);
+      if (lookahead == ':') ADVANCE(130);
+      if (('0' <= lookahead && lookahead <= '9') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(129);
+      if (lookahead == '-' ||
+          lookahead == '.' ||
+          ('G' <= lookahead && lookahead <= 'Z') ||
+          ('g' <= lookahead && lookahead <= 'z')) ADVANCE(117);
+      END_STATE();
+    case 130:
+      ACCEPT_TOKEN(sym_ipv6_address);
+      if (lookahead == '.' ||
+          ('0' <= lookahead && lookahead <= ':') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(130);
+      END_STATE();
+    case 131:
+      if (lookahead == ':') ADVANCE(133);
+      if (lookahead == ';') ADVANCE(120);
+      if (('\t' <= lookahead && lookahead <= '\r') ||
+          lookahead == ' ') SKIP(131);
+      if (('0' <= lookahead && lookahead <= '9') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(132);
+      END_STATE();
+    case 132:
+      if (lookahead == ':') ADVANCE(133);
+      if (('0' <= lookahead && lookahead <= '9') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(132);
+      END_STATE();
+    case 133:
+      if (lookahead == '/') ADVANCE(134);
+      if (('0' <= lookahead && lookahead <= ':') ||
+          ('A' <= lookahead && lookahead <= 'F') ||
+          ('a' <= lookahead && lookahead <= 'f')) ADVANCE(133);
+      END_STATE();
+    case 134:
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(135);
+      END_STATE();
+    case 135:
+      ACCEPT_TOKEN(sym_ipv6_network);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(136);
+      END_STATE();
+    case 136:
+      ACCEPT_TOKEN(sym_ipv6_network);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(137);
+      END_STATE();
+    case 137:
+      ACCEPT_TOKEN(sym_ipv6_network);
+      END_STATE();
+    case 138:
+      if (lookahead == 'p') ADVANCE(139);
+      END_STATE();
+    case 139:
+      if (lookahead == 'v') ADVANCE(140);
+      END_STATE();
+    case 140:
+      if (lookahead == '6') ADVANCE(141);
+      END_STATE();
+    case 141:
+      if (lookahead == '_') ADVANCE(142);
+      END_STATE();
+    case 142:
+      if (lookahead == 'p') ADVANCE(143);
+      END_STATE();
+    case 143:
+      if (lookahead == 'r') ADVANCE(144);
+      END_STATE();
+    case 144:
+      if (lookahead == 'e') ADVANCE(145);
+      END_STATE();
+    case 145:
+      if (lookahead == 'f') ADVANCE(146);
+      END_STATE();
+    case 146:
+      if (lookahead == 'i') ADVANCE(147);
+      END_STATE();
+    case 147:
+      if (lookahead == 'x') ADVANCE(148);
+      END_STATE();
+    case 148:
+      ACCEPT_TOKEN(sym_ipv6_prefix_keyword);
+      END_STATE();
+    case 149:
+      if (lookahead == 'p') ADVANCE(150);
+      END_STATE();
+    case 150:
+      if (lookahead == 'v') ADVANCE(151);
+      END_STATE();
+    case 151:
+      if (lookahead == '6') ADVANCE(152);
+      END_STATE();
+    case 152:
+      if (lookahead == '_') ADVANCE(153);
+      END_STATE();
+    case 153:
+      if (lookahead == 'd') ADVANCE(154);
+      END_STATE();
+    case 154:
+      if (lookahead == 'n') ADVANCE(155);
+      END_STATE();
+    case 155:
+      if (lookahead == 's') ADVANCE(156);
+      END_STATE();
+    case 156:
+      if (lookahead == '_') ADVANCE(157);
+      END_STATE();
+    case 157:
+      if (lookahead == 'f') ADVANCE(158);
+      END_STATE();
+    case 158:
+      if (lookahead == 'i') ADVANCE(159);
+      END_STATE();
+    case 159:
+      if (lookahead == 'l') ADVANCE(160);
+      END_STATE();
+    case 160:
+      if (lookahead == 'e') ADVANCE(161);
+      END_STATE();
+    case 161:
+      if (lookahead == '_') ADVANCE(162);
+      END_STATE();
+    case 162:
+      if (lookahead == 'n') ADVANCE(163);
+      END_STATE();
+    case 163:
+      if (lookahead == 'a') ADVANCE(164);
+      END_STATE();
+    case 164:
+      if (lookahead == 'm') ADVANCE(165);
+      END_STATE();
+    case 165:
+      if (lookahead == 'e') ADVANCE(166);
+      END_STATE();
+    case 166:
+      ACCEPT_TOKEN(sym_reverse_ipv6_dns_file_name_keyword);
+      END_STATE();
+    case 167:
+      if (lookahead == '6') ADVANCE(168);
+      END_STATE();
+    case 168:
+      if (lookahead == '_') ADVANCE(169);
+      END_STATE();
+    case 169:
+      if (lookahead == 'f') ADVANCE(170);
+      END_STATE();
+    case 170:
+      if (lookahead == 'i') ADVANCE(171);
+      END_STATE();
+    case 171:
+      if (lookahead == 'l') ADVANCE(172);
+      END_STATE();
+    case 172:
+      if (lookahead == 'e') ADVANCE(173);
+      END_STATE();
+    case 173:
+      if (lookahead == '_') ADVANCE(174);
+      END_STATE();
+    case 174:
+      if (lookahead == 'n') ADVANCE(175);
+      END_STATE();
+    case 175:
+      if (lookahead == 'a') ADVANCE(176);
+      END_STATE();
+    case 176:
+      if (lookahead == 'm') ADVANCE(177);
+      END_STATE();
+    case 177:
+      if (lookahead == 'e') ADVANCE(178);
+      END_STATE();
+    case 178:
+      ACCEPT_TOKEN(sym_dhcpv6_file_name_keyword);
+      END_STATE();
+    case 179:
+      if (lookahead == ';') ADVANCE(120);
+      if (('\t' <= lookahead && lookahead <= '\r') ||
+          lookahead == ' ') SKIP(179);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(180);
+      END_STATE();
+    case 180:
+      if (lookahead == '.') ADVANCE(192);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(181);
+      END_STATE();
+    case 181:
+      if (lookahead == '.') ADVANCE(192);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(182);
+      END_STATE();
+    case 182:
+      if (lookahead == '.') ADVANCE(192);
+      END_STATE();
+    case 183:
+      if (lookahead == '.') ADVANCE(193);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(184);
+      END_STATE();
+    case 184:
+      if (lookahead == '.') ADVANCE(193);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(185);
+      END_STATE();
+    case 185:
+      if (lookahead == '.') ADVANCE(193);
+      END_STATE();
+    case 186:
+      if (lookahead == '.') ADVANCE(194);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(187);
+      END_STATE();
+    case 187:
+      if (lookahead == '.') ADVANCE(194);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(188);
+      END_STATE();
+    case 188:
+      if (lookahead == '.') ADVANCE(194);
+      END_STATE();
+    case 189:
+      if (lookahead == '/') ADVANCE(195);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(190);
+      END_STATE();
+    case 190:
+      if (lookahead == '/') ADVANCE(195);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(191);
+      END_STATE();
+    case 191:
+      if (lookahead == '/') ADVANCE(195);
+      END_STATE();
+    case 192:
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(183);
+      END_STATE();
+    case 193:
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(186);
+      END_STATE();
+    case 194:
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(189);
+      END_STATE();
+    case 195:
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(196);
+      END_STATE();
+    case 196:
+      ACCEPT_TOKEN(sym_ipv4_network);
+      if (('0' <= lookahead && lookahead <= '9')) ADVANCE(197);
+      END_STATE();
+    case 197:
+      ACCEPT_TOKEN(sym_ipv4_network);
+      END_STATE();
+    case 198:
+      if (lookahead == 'u') ADVANCE(199);
+      END_STATE();
+    case 199:
+      if (lookahead == 'b') ADVANCE(200);
+      END_STATE();
+    case 200:
+      if (lookahead == 'n') ADVANCE(201);
+      END_STATE();
+    case 201:
+      if (lookahead == 'e') ADVANCE(202);
+      END_STATE();
+    case 202:
+      if (lookahead == 't') ADVANCE(203);
+      END_STATE();
+    case 203:
+      ACCEPT_TOKEN(sym_subnet_keyword);
+      END_STATE();
     default:
       return false;
   }
@@ -817,8 +1200,8 @@ static const TSLexerMode ts_lex_modes[STATE_COUNT] = {
   [13] = {.lex_state = 1},
   [14] = {.lex_state = 0},
   [15] = {.lex_state = 0},
-  [16] = {.lex_state = 13},
-  [17] = {.lex_state = 13},
+  [16] = {.lex_state = 128},
+  [17] = {.lex_state = 128},
   [18] = {.lex_state = 0},
   [19] = {.lex_state = 0},
   [20] = {.lex_state = 0},
@@ -845,6 +1228,24 @@ static const TSLexerMode ts_lex_modes[STATE_COUNT] = {
   [41] = {.lex_state = 0},
   [42] = {.lex_state = 0},
   [43] = {.lex_state = 125},
+  [44] = {.lex_state = 131},
+  [45] = {.lex_state = 1},
+  [46] = {.lex_state = 0},
+  [47] = {.lex_state = 14},
+  [48] = {.lex_state = 1},
+  [49] = {.lex_state = 0},
+  [50] = {.lex_state = 14},
+  [51] = {.lex_state = 1},
+  [52] = {.lex_state = 0},
+  [53] = {.lex_state = 128},
+  [54] = {.lex_state = 128},
+  [55] = {.lex_state = 128},
+  [56] = {.lex_state = 1},
+  [57] = {.lex_state = 1},
+  [58] = {.lex_state = 0},
+  [59] = {.lex_state = 179},
+  [60] = {.lex_state = 1},
+  [61] = {.lex_state = 0},
 };
 
 static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
@@ -854,6 +1255,10 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name_keyword] = ACTIONS(1),
     [sym_reverse_dns_file_name_keyword] = ACTIONS(1),
     [sym_dhcp_file_name_keyword] = ACTIONS(1),
+    [sym_ipv6_prefix_keyword] = ACTIONS(1),
+    [sym_reverse_ipv6_dns_file_name_keyword] = ACTIONS(1),
+    [sym_dhcpv6_file_name_keyword] = ACTIONS(1),
+    [sym_subnet_keyword] = ACTIONS(1),
     [sym_mac_address] = ACTIONS(1),
     [sym_ip_address] = ACTIONS(1),
     [sym_comment] = ACTIONS(3),
@@ -870,6 +1275,10 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name] = STATE(2),
     [sym_reverse_dns_file_name] = STATE(2),
     [sym_dhcp_file_name] = STATE(2),
+    [sym_ipv6_prefix] = STATE(2),
+    [sym_reverse_ipv6_dns_file_name] = STATE(2),
+    [sym_dhcpv6_file_name] = STATE(2),
+    [sym_subnet] = STATE(2),
     [sym__rule] = STATE(4),
     [sym_dns_prefix_section] = STATE(14),
     [aux_sym_source_file_repeat1] = STATE(2),
@@ -877,6 +1286,10 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name_keyword] = ACTIONS(7),
     [sym_reverse_dns_file_name_keyword] = ACTIONS(9),
     [sym_dhcp_file_name_keyword] = ACTIONS(11),
+    [sym_ipv6_prefix_keyword] = ACTIONS(118),
+    [sym_reverse_ipv6_dns_file_name_keyword] = ACTIONS(120),
+    [sym_dhcpv6_file_name_keyword] = ACTIONS(122),
+    [sym_subnet_keyword] = ACTIONS(170),
     [sym_mac_address] = ACTIONS(13),
     [sym_ip_address] = ACTIONS(15),
     [sym_comment] = ACTIONS(3),
@@ -889,6 +1302,10 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name] = STATE(3),
     [sym_reverse_dns_file_name] = STATE(3),
     [sym_dhcp_file_name] = STATE(3),
+    [sym_ipv6_prefix] = STATE(3),
+    [sym_reverse_ipv6_dns_file_name] = STATE(3),
+    [sym_dhcpv6_file_name] = STATE(3),
+    [sym_subnet] = STATE(3),
     [sym__rule] = STATE(4),
     [sym_dns_prefix_section] = STATE(20),
     [aux_sym_source_file_repeat1] = STATE(3),
@@ -896,6 +1313,10 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name_keyword] = ACTIONS(7),
     [sym_reverse_dns_file_name_keyword] = ACTIONS(9),
     [sym_dhcp_file_name_keyword] = ACTIONS(11),
+    [sym_ipv6_prefix_keyword] = ACTIONS(118),
+    [sym_reverse_ipv6_dns_file_name_keyword] = ACTIONS(120),
+    [sym_dhcpv6_file_name_keyword] = ACTIONS(122),
+    [sym_subnet_keyword] = ACTIONS(170),
     [sym_mac_address] = ACTIONS(13),
     [sym_ip_address] = ACTIONS(15),
     [sym_comment] = ACTIONS(3),
@@ -908,12 +1329,20 @@ static const uint16_t ts_parse_table[LARGE_STATE_COUNT][SYMBOL_COUNT] = {
     [sym_dns_file_name] = STATE(3),
     [sym_reverse_dns_file_name] = STATE(3),
     [sym_dhcp_file_name] = STATE(3),
+    [sym_ipv6_prefix] = STATE(3),
+    [sym_reverse_ipv6_dns_file_name] = STATE(3),
+    [sym_dhcpv6_file_name] = STATE(3),
+    [sym_subnet] = STATE(3),
     [sym__rule] = STATE(4),
     [aux_sym_source_file_repeat1] = STATE(3),
     [sym_domain_keyword] = ACTIONS(19),
     [sym_dns_file_name_keyword] = ACTIONS(22),
     [sym_reverse_dns_file_name_keyword] = ACTIONS(25),
     [sym_dhcp_file_name_keyword] = ACTIONS(28),
+    [sym_ipv6_prefix_keyword] = ACTIONS(124),
+    [sym_reverse_ipv6_dns_file_name_keyword] = ACTIONS(127),
+    [sym_dhcpv6_file_name_keyword] = ACTIONS(130),
+    [sym_subnet_keyword] = ACTIONS(172),
     [sym_mac_address] = ACTIONS(31),
     [sym_ip_address] = ACTIONS(34),
     [sym_comment] = ACTIONS(3),
@@ -925,81 +1354,109 @@ static const uint16_t ts_small_parse_table[] = {
   [0] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(39), 7,
+    ACTIONS(39), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [13] = 2,
+  [17] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(41), 7,
+    ACTIONS(41), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [26] = 2,
+  [34] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(43), 7,
+    ACTIONS(43), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [39] = 2,
+  [51] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(45), 7,
+    ACTIONS(45), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [52] = 2,
+  [68] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(47), 7,
+    ACTIONS(47), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [65] = 2,
+  [85] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(49), 7,
+    ACTIONS(49), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [78] = 2,
+  [102] = 2,
     ACTIONS(3), 1,
       sym_comment,
-    ACTIONS(51), 7,
+    ACTIONS(51), 11,
       sym_domain_keyword,
       sym_dns_file_name_keyword,
       sym_reverse_dns_file_name_keyword,
       sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
       sym_mac_address,
       sym_ip_address,
       sym_dns_prefix_keyword,
-  [91] = 4,
+  [119] = 4,
     ACTIONS(53), 1,
       anon_sym_LF,
     ACTIONS(55), 1,
@@ -1008,7 +1465,7 @@ static const uint16_t ts_small_parse_table[] = {
       sym_comment,
     STATE(12), 1,
       aux_sym__rule_repeat1,
-  [104] = 4,
+  [132] = 4,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(59), 1,
@@ -1017,7 +1474,7 @@ static const uint16_t ts_small_parse_table[] = {
       sym_hostname,
     STATE(12), 1,
       aux_sym__rule_repeat1,
-  [117] = 4,
+  [145] = 4,
     ACTIONS(55), 1,
       sym_hostname,
     ACTIONS(57), 1,
@@ -1026,218 +1483,406 @@ static const uint16_t ts_small_parse_table[] = {
       anon_sym_LF,
     STATE(12), 1,
       aux_sym__rule_repeat1,
-  [130] = 3,
+  [158] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(66), 1,
       sym_dns_suffix_keyword,
     STATE(19), 1,
       sym_dns_suffix_section,
-  [140] = 3,
+  [168] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(68), 1,
       sym_string_delimiter,
     STATE(34), 1,
       sym_string,
-  [150] = 3,
+  [178] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(70), 1,
       sym_hostname,
+    ACTIONS(133), 1,
+      sym_ipv6_address,
     STATE(13), 1,
       aux_sym__rule_repeat1,
-  [160] = 3,
+    STATE(54), 1,
+      aux_sym__rule_repeat2,
+  [194] = 5,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(72), 1,
       sym_hostname,
+    ACTIONS(135), 1,
+      sym_ipv6_address,
     STATE(11), 1,
       aux_sym__rule_repeat1,
-  [170] = 3,
+    STATE(53), 1,
+      aux_sym__rule_repeat2,
+  [210] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(68), 1,
       sym_string_delimiter,
     STATE(33), 1,
       sym_string,
-  [180] = 3,
+  [220] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(74), 1,
       sym_dhcp_prefix_keyword,
     STATE(24), 1,
       sym_dhcp_prefix_section,
-  [190] = 3,
+  [230] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(66), 1,
       sym_dns_suffix_keyword,
     STATE(22), 1,
       sym_dns_suffix_section,
-  [200] = 3,
+  [240] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(76), 1,
       sym_string_delimiter,
     STATE(39), 1,
       sym_string,
-  [210] = 3,
+  [250] = 3,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(74), 1,
       sym_dhcp_prefix_keyword,
     STATE(40), 1,
       sym_dhcp_prefix_section,
-  [220] = 2,
+  [260] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(78), 2,
       sym_dns_suffix_keyword,
       sym_dhcp_prefix_keyword,
-  [228] = 2,
+  [268] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(80), 1,
       ts_builtin_sym_end,
-  [235] = 2,
+  [275] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(82), 1,
       anon_sym_LF,
-  [242] = 2,
+  [282] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(84), 1,
       anon_sym_LF,
-  [249] = 2,
+  [289] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(86), 1,
       anon_sym_LF,
-  [256] = 2,
+  [296] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(88), 1,
       anon_sym_LF,
-  [263] = 2,
+  [303] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(90), 1,
       sym_hostname,
-  [270] = 2,
+  [310] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(92), 1,
       sym_filename,
-  [277] = 2,
+  [317] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(94), 1,
       sym_string_content,
-  [284] = 2,
+  [324] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(96), 1,
       sym_string_delimiter,
-  [291] = 2,
+  [331] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(98), 1,
       sym_dhcp_prefix_keyword,
-  [298] = 2,
+  [338] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(100), 1,
       sym_dns_suffix_keyword,
-  [305] = 2,
+  [345] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(102), 1,
       ts_builtin_sym_end,
-  [312] = 2,
+  [352] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(104), 1,
       sym_filename,
-  [319] = 2,
+  [359] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(106), 1,
       sym_filename,
-  [326] = 2,
+  [366] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(108), 1,
       sym_ip_address,
-  [333] = 2,
+  [373] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(110), 1,
       ts_builtin_sym_end,
-  [340] = 2,
+  [380] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(112), 1,
       ts_builtin_sym_end,
-  [347] = 2,
+  [387] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(78), 1,
       ts_builtin_sym_end,
-  [354] = 2,
+  [394] = 2,
     ACTIONS(3), 1,
       sym_comment,
     ACTIONS(114), 1,
       sym_string_delimiter,
-  [361] = 2,
+  [401] = 2,
     ACTIONS(57), 1,
       sym_comment,
     ACTIONS(116), 1,
       sym_string_content,
+  [408] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(139), 1,
+      sym_ipv6_network,
+  [415] = 2,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(141), 1,
+      anon_sym_LF,
+  [422] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(143), 11,
+      sym_domain_keyword,
+      sym_dns_file_name_keyword,
+      sym_reverse_dns_file_name_keyword,
+      sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
+      sym_mac_address,
+      sym_ip_address,
+      sym_dns_prefix_keyword,
+  [439] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(145), 1,
+      sym_filename,
+  [446] = 2,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(147), 1,
+      anon_sym_LF,
+  [453] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(149), 11,
+      sym_domain_keyword,
+      sym_dns_file_name_keyword,
+      sym_reverse_dns_file_name_keyword,
+      sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
+      sym_mac_address,
+      sym_ip_address,
+      sym_dns_prefix_keyword,
+  [470] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(151), 1,
+      sym_filename,
+  [477] = 2,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(153), 1,
+      anon_sym_LF,
+  [484] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(155), 11,
+      sym_domain_keyword,
+      sym_dns_file_name_keyword,
+      sym_reverse_dns_file_name_keyword,
+      sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
+      sym_mac_address,
+      sym_ip_address,
+      sym_dns_prefix_keyword,
+  [501] = 5,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(137), 1,
+      sym_ipv6_address,
+    ACTIONS(157), 1,
+      sym_hostname,
+    STATE(56), 1,
+      aux_sym__rule_repeat1,
+    STATE(55), 1,
+      aux_sym__rule_repeat2,
+  [517] = 5,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(137), 1,
+      sym_ipv6_address,
+    ACTIONS(159), 1,
+      sym_hostname,
+    STATE(57), 1,
+      aux_sym__rule_repeat1,
+    STATE(55), 1,
+      aux_sym__rule_repeat2,
+  [533] = 3,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(161), 1,
+      sym_ipv6_address,
+    ACTIONS(164), 1,
+      sym_hostname,
+  [543] = 4,
+    ACTIONS(55), 1,
+      sym_hostname,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(64), 1,
+      anon_sym_LF,
+    STATE(12), 1,
+      aux_sym__rule_repeat1,
+  [556] = 4,
+    ACTIONS(55), 1,
+      sym_hostname,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(166), 1,
+      anon_sym_LF,
+    STATE(12), 1,
+      aux_sym__rule_repeat1,
+  [569] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(168), 11,
+      sym_domain_keyword,
+      sym_dns_file_name_keyword,
+      sym_reverse_dns_file_name_keyword,
+      sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
+      sym_mac_address,
+      sym_ip_address,
+      sym_dns_prefix_keyword,
+  [586] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(175), 1,
+      sym_ipv4_network,
+  [593] = 2,
+    ACTIONS(57), 1,
+      sym_comment,
+    ACTIONS(177), 1,
+      anon_sym_LF,
+  [600] = 2,
+    ACTIONS(3), 1,
+      sym_comment,
+    ACTIONS(179), 11,
+      sym_domain_keyword,
+      sym_dns_file_name_keyword,
+      sym_reverse_dns_file_name_keyword,
+      sym_dhcp_file_name_keyword,
+      sym_ipv6_prefix_keyword,
+      sym_reverse_ipv6_dns_file_name_keyword,
+      sym_dhcpv6_file_name_keyword,
+      sym_subnet_keyword,
+      sym_mac_address,
+      sym_ip_address,
+      sym_dns_prefix_keyword,
 };
 
 static const uint32_t ts_small_parse_table_map[] = {
   [SMALL_STATE(4)] = 0,
-  [SMALL_STATE(5)] = 13,
-  [SMALL_STATE(6)] = 26,
-  [SMALL_STATE(7)] = 39,
-  [SMALL_STATE(8)] = 52,
-  [SMALL_STATE(9)] = 65,
-  [SMALL_STATE(10)] = 78,
-  [SMALL_STATE(11)] = 91,
-  [SMALL_STATE(12)] = 104,
-  [SMALL_STATE(13)] = 117,
-  [SMALL_STATE(14)] = 130,
-  [SMALL_STATE(15)] = 140,
-  [SMALL_STATE(16)] = 150,
-  [SMALL_STATE(17)] = 160,
-  [SMALL_STATE(18)] = 170,
-  [SMALL_STATE(19)] = 180,
-  [SMALL_STATE(20)] = 190,
-  [SMALL_STATE(21)] = 200,
-  [SMALL_STATE(22)] = 210,
-  [SMALL_STATE(23)] = 220,
-  [SMALL_STATE(24)] = 228,
-  [SMALL_STATE(25)] = 235,
-  [SMALL_STATE(26)] = 242,
-  [SMALL_STATE(27)] = 249,
-  [SMALL_STATE(28)] = 256,
-  [SMALL_STATE(29)] = 263,
-  [SMALL_STATE(30)] = 270,
-  [SMALL_STATE(31)] = 277,
-  [SMALL_STATE(32)] = 284,
-  [SMALL_STATE(33)] = 291,
-  [SMALL_STATE(34)] = 298,
-  [SMALL_STATE(35)] = 305,
-  [SMALL_STATE(36)] = 312,
-  [SMALL_STATE(37)] = 319,
-  [SMALL_STATE(38)] = 326,
-  [SMALL_STATE(39)] = 333,
-  [SMALL_STATE(40)] = 340,
-  [SMALL_STATE(41)] = 347,
-  [SMALL_STATE(42)] = 354,
-  [SMALL_STATE(43)] = 361,
+  [SMALL_STATE(5)] = 17,
+  [SMALL_STATE(6)] = 34,
+  [SMALL_STATE(7)] = 51,
+  [SMALL_STATE(8)] = 68,
+  [SMALL_STATE(9)] = 85,
+  [SMALL_STATE(10)] = 102,
+  [SMALL_STATE(11)] = 119,
+  [SMALL_STATE(12)] = 132,
+  [SMALL_STATE(13)] = 145,
+  [SMALL_STATE(14)] = 158,
+  [SMALL_STATE(15)] = 168,
+  [SMALL_STATE(16)] = 178,
+  [SMALL_STATE(17)] = 194,
+  [SMALL_STATE(18)] = 210,
+  [SMALL_STATE(19)] = 220,
+  [SMALL_STATE(20)] = 230,
+  [SMALL_STATE(21)] = 240,
+  [SMALL_STATE(22)] = 250,
+  [SMALL_STATE(23)] = 260,
+  [SMALL_STATE(24)] = 268,
+  [SMALL_STATE(25)] = 275,
+  [SMALL_STATE(26)] = 282,
+  [SMALL_STATE(27)] = 289,
+  [SMALL_STATE(28)] = 296,
+  [SMALL_STATE(29)] = 303,
+  [SMALL_STATE(30)] = 310,
+  [SMALL_STATE(31)] = 317,
+  [SMALL_STATE(32)] = 324,
+  [SMALL_STATE(33)] = 331,
+  [SMALL_STATE(34)] = 338,
+  [SMALL_STATE(35)] = 345,
+  [SMALL_STATE(36)] = 352,
+  [SMALL_STATE(37)] = 359,
+  [SMALL_STATE(38)] = 366,
+  [SMALL_STATE(39)] = 373,
+  [SMALL_STATE(40)] = 380,
+  [SMALL_STATE(41)] = 387,
+  [SMALL_STATE(42)] = 394,
+  [SMALL_STATE(43)] = 401,
+  [SMALL_STATE(44)] = 408,
+  [SMALL_STATE(45)] = 415,
+  [SMALL_STATE(46)] = 422,
+  [SMALL_STATE(47)] = 439,
+  [SMALL_STATE(48)] = 446,
+  [SMALL_STATE(49)] = 453,
+  [SMALL_STATE(50)] = 470,
+  [SMALL_STATE(51)] = 477,
+  [SMALL_STATE(52)] = 484,
+  [SMALL_STATE(53)] = 501,
+  [SMALL_STATE(54)] = 517,
+  [SMALL_STATE(55)] = 533,
+  [SMALL_STATE(56)] = 543,
+  [SMALL_STATE(57)] = 556,
+  [SMALL_STATE(58)] = 569,
+  [SMALL_STATE(59)] = 586,
+  [SMALL_STATE(60)] = 593,
+  [SMALL_STATE(61)] = 600,
 };
 
 static const TSParseActionEntry ts_parse_actions[] = {
@@ -1297,6 +1942,35 @@ static const TSParseActionEntry ts_parse_actions[] = {
   [112] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_source_file, 4, 0, 0),
   [114] = {.entry = {.count = 1, .reusable = true}}, SHIFT(41),
   [116] = {.entry = {.count = 1, .reusable = false}}, SHIFT(42),
+  [118] = {.entry = {.count = 1, .reusable = true}}, SHIFT(44),
+  [120] = {.entry = {.count = 1, .reusable = true}}, SHIFT(47),
+  [122] = {.entry = {.count = 1, .reusable = true}}, SHIFT(50),
+  [124] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_source_file_repeat1, 2, 0, 0), SHIFT_REPEAT(44),
+  [127] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_source_file_repeat1, 2, 0, 0), SHIFT_REPEAT(47),
+  [130] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_source_file_repeat1, 2, 0, 0), SHIFT_REPEAT(50),
+  [133] = {.entry = {.count = 1, .reusable = true}}, SHIFT(54),
+  [135] = {.entry = {.count = 1, .reusable = true}}, SHIFT(53),
+  [137] = {.entry = {.count = 1, .reusable = true}}, SHIFT(55),
+  [139] = {.entry = {.count = 1, .reusable = true}}, SHIFT(45),
+  [141] = {.entry = {.count = 1, .reusable = true}}, SHIFT(46),
+  [143] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_ipv6_prefix, 3, 0, 0),
+  [145] = {.entry = {.count = 1, .reusable = true}}, SHIFT(48),
+  [147] = {.entry = {.count = 1, .reusable = true}}, SHIFT(49),
+  [149] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_reverse_ipv6_dns_file_name, 3, 0, 0),
+  [151] = {.entry = {.count = 1, .reusable = true}}, SHIFT(51),
+  [153] = {.entry = {.count = 1, .reusable = true}}, SHIFT(52),
+  [155] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_dhcpv6_file_name, 3, 0, 0),
+  [157] = {.entry = {.count = 1, .reusable = true}}, SHIFT(56),
+  [159] = {.entry = {.count = 1, .reusable = true}}, SHIFT(57),
+  [161] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym__rule_repeat2, 2, 0, 0), SHIFT_REPEAT(55),
+  [164] = {.entry = {.count = 1, .reusable = true}}, REDUCE(aux_sym__rule_repeat2, 2, 0, 0),
+  [166] = {.entry = {.count = 1, .reusable = true}}, SHIFT(58),
+  [168] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym__rule, 5, 0, 0),
+  [170] = {.entry = {.count = 1, .reusable = true}}, SHIFT(59),
+  [172] = {.entry = {.count = 2, .reusable = true}}, REDUCE(aux_sym_source_file_repeat1, 2, 0, 0), SHIFT_REPEAT(59),
+  [175] = {.entry = {.count = 1, .reusable = true}}, SHIFT(60),
+  [177] = {.entry = {.count = 1, .reusable = true}}, SHIFT(61),
+  [179] = {.entry = {.count = 1, .reusable = true}}, REDUCE(sym_subnet, 3, 0, 0),
 };
 
 #ifdef __cplusplus